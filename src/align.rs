@@ -0,0 +1,71 @@
+use crate::PNMImage;
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// An integer type the pixel data can be reinterpreted as, see [`PNMImage::pixel_data_aligned`]
+///
+/// This trait is sealed and implemented for `u8`, `u16`, `u32` and `u64`, for which every bit
+/// pattern is a valid value.
+pub trait Word: Copy + sealed::Sealed {}
+
+macro_rules! word {
+    ($($ty:ty),*) => {
+        $(
+            impl sealed::Sealed for $ty {}
+            impl Word for $ty {}
+        )*
+    };
+}
+
+word!(u8, u16, u32, u64);
+
+impl PNMImage<'_> {
+    /// Splits the pixel data into an unaligned prefix, a middle part of words aligned for `A`
+    /// and an unaligned suffix, like [`slice::align_to`].
+    ///
+    /// The words are in native byte order. Trailing bytes after the pixel data described by the
+    /// header are not included. Use [`encode_aligned`](Self::encode_aligned) to write images whose
+    /// pixel data starts aligned, so the prefix is empty.
+    pub fn pixel_data_aligned<A: Word>(&self) -> (&[u8], &[A], &[u8]) {
+        // SAFETY: `Word` is only implemented for integers, which are valid for every bit pattern
+        unsafe { self.raster().align_to::<A>() }
+    }
+
+    /// Returns whether the pixel data starts at an address that is a multiple of `align`.
+    pub fn is_pixel_data_aligned(&self, align: usize) -> bool {
+        align != 0 && (self.pixel_data().as_ptr() as usize).is_multiple_of(align)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[repr(align(8))]
+    struct Aligned<const N: usize>([u8; N]);
+
+    #[test]
+    fn pixel_data_aligned() {
+        // the header takes up 12 bytes, so the pixel data is 4-byte aligned
+        let raw_img = Aligned(*b"P5\n10 1\n255\n\x01\x02\x03\x04\x05\x06\x07\x08\x09\x0a");
+        let gray_img = PNMImage::from_parse(&raw_img.0[..]).unwrap();
+        assert!(gray_img.is_pixel_data_aligned(4));
+        assert!(!gray_img.is_pixel_data_aligned(8));
+
+        let (prefix, words, suffix) = gray_img.pixel_data_aligned::<u32>();
+        assert!(prefix.is_empty());
+        assert_eq!(suffix, [9, 10]);
+        assert_eq!(
+            words,
+            [
+                u32::from_ne_bytes([1, 2, 3, 4]),
+                u32::from_ne_bytes([5, 6, 7, 8])
+            ]
+        );
+
+        let (prefix, words, suffix) = gray_img.pixel_data_aligned::<u64>();
+        assert_eq!((prefix.len(), words.len(), suffix.len()), (4, 0, 6));
+    }
+}
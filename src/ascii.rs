@@ -0,0 +1,184 @@
+use crate::PNMError::{self, *};
+use crate::PNMImage;
+use crate::{Expected, Field, RecoveredHeader};
+
+impl<'a> PNMImage<'a> {
+    /// Parses a plain (ASCII) PPM, PGM or PBM image (P3, P2 or P1) from a byte array
+    ///
+    /// The samples are decoded into `scratch` in the same layout as the matching binary format,
+    /// so the returned image behaves exactly like one parsed by [`from_parse`](Self::from_parse).
+    /// If `scratch` is too small, the error reports the required size so the call can be retried.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - A byte array containing the P3, P2 or P1 image data
+    /// * `scratch` - A buffer receiving the decoded pixel data
+    ///
+    /// # Returns
+    ///
+    /// A Result object containing the parsed PNMImage if successful, otherwise a PNMError
+    pub fn from_parse_ascii(bytes: &'a [u8], scratch: &'a mut [u8]) -> Result<Self, PNMError> {
+        let (mut image, offset) = Self::parse_as(bytes, true)?;
+        let required = image.raster_len().ok_or(UnexpectedEOF)?;
+        if scratch.len() < required {
+            return Err(ScratchTooSmall { required });
+        }
+
+        let mut idx = offset;
+        if let Some(row_len) = image.row_len().filter(|&len| len > 0) {
+            for row in scratch[..required].chunks_exact_mut(row_len) {
+                idx = decode_row(bytes, idx, &image, row)?;
+            }
+        }
+
+        image.set_pixel_data(&scratch[..required]);
+        Ok(image)
+    }
+}
+
+/// Decodes the row of plain samples starting at or after `idx` into `row`, laid out like binary pixel data.
+/// Returns the position following the row.
+pub(crate) fn decode_row(
+    bytes: &[u8],
+    mut idx: usize,
+    header: &PNMImage<'_>,
+    row: &mut [u8],
+) -> Result<usize, PNMError> {
+    if header.is_bitmap() {
+        row.fill(0);
+        for x in 0..header.width() {
+            let bit;
+            (bit, idx) = parse_bit(bytes, idx, header)?;
+            row[x / 8] |= bit << (7 - x % 8);
+        }
+        return Ok(idx);
+    }
+    let sample_size = header.sample_size();
+    for sample in row.chunks_exact_mut(sample_size) {
+        let value;
+        (value, idx) = parse_sample(bytes, idx, header)?;
+        if sample_size == 2 {
+            sample.copy_from_slice(&(value as u16).to_be_bytes());
+        } else {
+            sample[0] = value as u8;
+        }
+    }
+    Ok(idx)
+}
+
+/// Parses the bit starting at or after `idx`. Bits of plain bitmaps need not be separated by whitespace.
+/// Returns the bit and the position following it.
+fn parse_bit(bytes: &[u8], mut idx: usize, header: &PNMImage<'_>) -> Result<(u8, usize), PNMError> {
+    while bytes.get(idx).is_some_and(u8::is_ascii_whitespace) {
+        idx += 1;
+    }
+    match bytes.get(idx) {
+        Some(b @ (b'0' | b'1')) => Ok((b - b'0', idx + 1)),
+        Some(&got) => Err(ParseError {
+            pos: idx,
+            got,
+            ctx: "expected bit.",
+            expected: Expected::Digit,
+            field: Field::PixelData,
+            recovered: RecoveredHeader {
+                width: Some(header.width()),
+                height: Some(header.height()),
+                maximum_pixel: None,
+            },
+        }),
+        None => Err(UnexpectedEOF),
+    }
+}
+
+/// Parses the whitespace separated decimal sample starting at or after `idx`.
+/// Returns the sample and the position following it.
+pub(crate) fn parse_sample(
+    bytes: &[u8],
+    mut idx: usize,
+    header: &PNMImage<'_>,
+) -> Result<(usize, usize), PNMError> {
+    let maximum_pixel = header.maximum_pixel();
+    let recovered = RecoveredHeader {
+        width: Some(header.width()),
+        height: Some(header.height()),
+        maximum_pixel: Some(maximum_pixel),
+    };
+    while bytes.get(idx).is_some_and(u8::is_ascii_whitespace) {
+        idx += 1;
+    }
+    let start = idx;
+    let mut acc: usize = 0;
+    while let Some(&b) = bytes.get(idx).filter(|b| !b.is_ascii_whitespace()) {
+        if !b.is_ascii_digit() {
+            return Err(ParseError {
+                pos: idx,
+                got: b,
+                ctx: "expected digit.",
+                expected: Expected::Digit,
+                field: Field::PixelData,
+                recovered,
+            });
+        }
+        acc = acc.saturating_mul(10).saturating_add((b - b'0') as usize);
+        if acc > maximum_pixel {
+            return Err(ParseError {
+                pos: idx,
+                got: b,
+                ctx: "sample exceeds maximum pixel value.",
+                expected: Expected::Whitespace,
+                field: Field::PixelData,
+                recovered,
+            });
+        }
+        idx += 1;
+    }
+    if idx == start {
+        return Err(UnexpectedEOF);
+    }
+    Ok((acc, idx))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_ascii() {
+        let raw_img = b"P3\n# plain\n2 2\n255\n255 0 0  0 255 0\n0 0 255\n255 255 255\n";
+        let mut scratch = [0; 12];
+        let ppm_img = PNMImage::from_parse_ascii(raw_img, &mut scratch).unwrap();
+
+        assert_eq!(ppm_img.comment(), "# plain");
+        assert_eq!(ppm_img.width(), 2);
+        assert_eq!(ppm_img.pixel_rgb(1, 0), Some((0, 255, 0)));
+        assert_eq!(ppm_img.pixel_rgb(0, 1), Some((0, 0, 255)));
+        assert_eq!(ppm_img.pixel_rgb(1, 1), Some((255, 255, 255)));
+
+        let mut small = [0; 4];
+        assert!(matches!(
+            PNMImage::from_parse_ascii(raw_img, &mut small),
+            Err(ScratchTooSmall { required: 12 })
+        ));
+    }
+
+    #[test]
+    fn parse_ascii_errors() {
+        let mut scratch = [0; 12];
+        assert!(matches!(
+            PNMImage::from_parse_ascii(b"P3\n1 1\n255\n1 2", &mut scratch),
+            Err(UnexpectedEOF)
+        ));
+        assert!(matches!(
+            PNMImage::from_parse_ascii(b"P3\n1 1\n15\n1 2 16", &mut scratch),
+            Err(ParseError { pos: 15, .. })
+        ));
+        assert!(matches!(
+            PNMImage::from_parse_ascii(b"P3\n1 1\n255\n1 x 3", &mut scratch),
+            Err(ParseError { got: b'x', .. })
+        ));
+        assert!(matches!(
+            PNMImage::from_parse_ascii(include_bytes!("./binary.ppm"), &mut scratch),
+            Err(UnsupportedPNMFormat)
+        ));
+    }
+}
@@ -0,0 +1,156 @@
+use crate::PNMError::{self, *};
+use crate::PNMImage;
+
+/// Returns the 6-bit value of a character of the standard base64 alphabet.
+fn sextet(c: u8) -> Option<u8> {
+    match c {
+        b'A'..=b'Z' => Some(c - b'A'),
+        b'a'..=b'z' => Some(c - b'a' + 26),
+        b'0'..=b'9' => Some(c - b'0' + 52),
+        b'+' => Some(62),
+        b'/' => Some(63),
+        _ => None,
+    }
+}
+
+/// Decodes base64 `input` into `out`, skipping whitespace, and returns the number of bytes written.
+fn decode(input: &str, out: &mut [u8]) -> Result<usize, PNMError> {
+    let input = input.trim_end_matches(|c: char| c == '=' || c.is_ascii_whitespace());
+    let digits = input.bytes().filter(|c| !c.is_ascii_whitespace()).count();
+    let required = digits * 3 / 4;
+    let out = out
+        .get_mut(..required)
+        .ok_or(ScratchTooSmall { required })?;
+
+    let (mut acc, mut bits, mut len) = (0u32, 0, 0);
+    for (pos, c) in input.bytes().enumerate() {
+        if c.is_ascii_whitespace() {
+            continue;
+        }
+        acc = acc << 6 | sextet(c).ok_or(InvalidBase64 { pos })? as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out[len] = (acc >> bits) as u8;
+            len += 1;
+        }
+    }
+    // a single leftover digit cannot encode a whole byte
+    if digits % 4 == 1 {
+        return Err(InvalidBase64 { pos: input.len() });
+    }
+    Ok(len)
+}
+
+impl<'a> PNMImage<'a> {
+    /// Decodes a base64 encoded binary PNM image into `scratch` and parses it
+    ///
+    /// Whitespace, such as line breaks, and trailing `=` padding are ignored.
+    /// If `scratch` is too small, the error reports the required size so the call can be retried.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The base64 encoded image, using the standard alphabet
+    /// * `scratch` - A buffer receiving the decoded image
+    ///
+    /// # Returns
+    ///
+    /// A Result object containing the parsed PNMImage borrowing `scratch` if successful, otherwise a PNMError
+    pub fn from_base64(input: &str, scratch: &'a mut [u8]) -> Result<Self, PNMError> {
+        let len = decode(input, scratch)?;
+        let scratch: &'a [u8] = scratch;
+        Self::from_parse(&scratch[..len])
+    }
+
+    /// Decodes a base64 `data:` URI, such as `data:image/x-portable-pixmap;base64,UDYK...`,
+    /// into `scratch` and parses it
+    ///
+    /// The media type is not checked, but the URI needs to be base64 encoded.
+    /// See [`from_base64`](Self::from_base64) for the decoding.
+    ///
+    /// # Arguments
+    ///
+    /// * `uri` - The data URI holding the image
+    /// * `scratch` - A buffer receiving the decoded image
+    ///
+    /// # Returns
+    ///
+    /// A Result object containing the parsed PNMImage borrowing `scratch` if successful, otherwise a PNMError
+    pub fn from_data_uri(uri: &str, scratch: &'a mut [u8]) -> Result<Self, PNMError> {
+        let (meta, data) = uri
+            .trim()
+            .strip_prefix("data:")
+            .and_then(|uri| uri.split_once(','))
+            .ok_or(NotPNMFormat)?;
+        if !meta.ends_with(";base64") {
+            return Err(NotPNMFormat);
+        }
+        Self::from_base64(data, scratch)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl crate::OwnedPNMImage {
+    /// Decodes a base64 encoded binary PNM image into an owned image, see [`PNMImage::from_base64`].
+    pub fn from_base64(input: &str) -> Result<Self, PNMError> {
+        let mut scratch = alloc::vec![0; decoded_len(input)];
+        Ok(PNMImage::from_base64(input, &mut scratch)?.into_owned())
+    }
+
+    /// Decodes a base64 `data:` URI into an owned image, see [`PNMImage::from_data_uri`].
+    pub fn from_data_uri(uri: &str) -> Result<Self, PNMError> {
+        let mut scratch = alloc::vec![0; decoded_len(uri)];
+        Ok(PNMImage::from_data_uri(uri, &mut scratch)?.into_owned())
+    }
+}
+
+/// Returns an upper bound of the number of bytes `input` decodes to.
+#[cfg(feature = "alloc")]
+fn decoded_len(input: &str) -> usize {
+    input.len() * 3 / 4
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn from_base64() {
+        let mut scratch = [0; 32];
+        // P5\n2 1\n255\n\x10\x20
+        let gray_img = PNMImage::from_base64("UDUKMiAx\nCjI1NQoQIA==", &mut scratch).unwrap();
+        assert_eq!(gray_img.width(), 2);
+        assert_eq!(gray_img.pixel_gray(1, 0), Some(0x20));
+
+        let gray_img = PNMImage::from_data_uri(
+            "data:image/x-portable-graymap;base64,UDUKMiAxCjI1NQoQIA",
+            &mut scratch,
+        )
+        .unwrap();
+        assert_eq!(gray_img.pixel_gray(0, 0), Some(0x10));
+
+        assert_eq!(
+            PNMImage::from_base64("UDUKMiAxCjI1NQoQIA==", &mut [0; 8]).unwrap_err(),
+            ScratchTooSmall { required: 13 }
+        );
+        assert_eq!(
+            PNMImage::from_base64("UDUK*iAx", &mut scratch).unwrap_err(),
+            InvalidBase64 { pos: 4 }
+        );
+        assert_eq!(
+            PNMImage::from_data_uri("data:image/x-portable-graymap,P5", &mut scratch).unwrap_err(),
+            NotPNMFormat
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn owned_from_base64() {
+        let owned = crate::OwnedPNMImage::from_data_uri(
+            "data:image/x-portable-graymap;base64,UDUKMiAxCjI1NQoQIA==",
+        )
+        .unwrap();
+        assert_eq!(owned.as_image().pixel_gray(1, 0), Some(0x20));
+        assert!(crate::OwnedPNMImage::from_base64("").is_err());
+    }
+}
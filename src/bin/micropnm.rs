@@ -0,0 +1,168 @@
+//! Command line tool for inspecting and converting PNM files.
+
+use std::process::ExitCode;
+use std::{env, fs};
+
+use micropnm::{EncodeError, Encoding, PNMError, PNMImage};
+
+const USAGE: &str = "usage:
+    micropnm info <file>
+    micropnm pixel <file> <x> <y>
+    micropnm convert <p3|p5|p6> <input> <output>
+    micropnm diff <a> <b>";
+
+/// How many differing pixels `diff` lists before summarizing
+const MAX_LISTED_DIFFS: usize = 10;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let result = match args.as_slice() {
+        ["info", file] => info(file),
+        ["pixel", file, x, y] => pixel(file, x, y),
+        ["convert", format, input, output] => convert(format, input, output),
+        ["diff", a, b] => diff(a, b),
+        _ => {
+            eprintln!("{}", USAGE);
+            return ExitCode::from(2);
+        }
+    };
+    match result {
+        Ok(code) => code,
+        Err(msg) => {
+            eprintln!("micropnm: {}", msg);
+            ExitCode::from(2)
+        }
+    }
+}
+
+/// A PNM file read from disk, along with the scratch buffer plain formats are decoded into
+struct PNMFile {
+    path: String,
+    bytes: Vec<u8>,
+    scratch: Vec<u8>,
+}
+
+impl PNMFile {
+    fn read(path: &str) -> Result<Self, String> {
+        let bytes = fs::read(path).map_err(|err| format!("{}: {}", path, err))?;
+        Ok(Self {
+            path: path.to_owned(),
+            bytes,
+            scratch: Vec::new(),
+        })
+    }
+
+    /// Returns the magic number of the file, e.g. `P6`.
+    fn magic(&self) -> &str {
+        self.bytes
+            .get(..2)
+            .and_then(|magic| std::str::from_utf8(magic).ok())
+            .unwrap_or("??")
+    }
+
+    fn image(&mut self) -> Result<PNMImage<'_>, String> {
+        let result = if matches!(self.magic(), "P3" | "P2" | "P1") {
+            if let Err(PNMError::ScratchTooSmall { required }) =
+                PNMImage::from_parse_ascii(&self.bytes, &mut [])
+            {
+                self.scratch.resize(required, 0);
+            }
+            PNMImage::from_parse_ascii(&self.bytes, &mut self.scratch)
+        } else {
+            PNMImage::from_parse(&self.bytes)
+        };
+        result.map_err(|err| format!("{}: {:?}", self.path, err))
+    }
+}
+
+fn info(path: &str) -> Result<ExitCode, String> {
+    let mut file = PNMFile::read(path)?;
+    let magic = file.magic().to_owned();
+    let image = file.image()?;
+    println!("format: {}", magic);
+    println!("width: {}", image.width());
+    println!("height: {}", image.height());
+    println!("maximum pixel: {}", image.maximum_pixel());
+    println!("comment: {}", image.comment());
+    Ok(ExitCode::SUCCESS)
+}
+
+fn pixel(path: &str, x: &str, y: &str) -> Result<ExitCode, String> {
+    let x = x
+        .parse()
+        .map_err(|_| format!("invalid x coordinate: {}", x))?;
+    let y = y
+        .parse()
+        .map_err(|_| format!("invalid y coordinate: {}", y))?;
+    let mut file = PNMFile::read(path)?;
+    let image = file.image()?;
+    if x >= image.width() {
+        return Err(format!("x coordinate {} is outside the image", x));
+    }
+    let (r, g, b) = image
+        .pixel_rgb(x, y)
+        .ok_or_else(|| format!("pixel ({}, {}) is outside the image", x, y))?;
+    println!("{} {} {}", r, g, b);
+    Ok(ExitCode::SUCCESS)
+}
+
+fn convert(format: &str, input: &str, output: &str) -> Result<ExitCode, String> {
+    let encoding = match format {
+        "p3" | "P3" => Encoding::PPMAscii,
+        "p5" | "P5" => Encoding::PGMBinary,
+        "p6" | "P6" => Encoding::PPMBinary,
+        _ => return Err(format!("unsupported output format: {}", format)),
+    };
+    let mut file = PNMFile::read(input)?;
+    let image = file.image()?;
+
+    let mut out = Vec::new();
+    if let Err(EncodeError::BufferTooSmall { required }) = image.encode(encoding, &mut out) {
+        out.resize(required, 0);
+    }
+    let len = image
+        .encode(encoding, &mut out)
+        .map_err(|err| format!("{}: {:?}", input, err))?;
+    fs::write(output, &out[..len]).map_err(|err| format!("{}: {}", output, err))?;
+    Ok(ExitCode::SUCCESS)
+}
+
+fn diff(a: &str, b: &str) -> Result<ExitCode, String> {
+    let mut file_a = PNMFile::read(a)?;
+    let mut file_b = PNMFile::read(b)?;
+    let image_a = file_a.image()?;
+    let image_b = file_b.image()?;
+
+    let (width, height) = (image_a.width(), image_a.height());
+    if (width, height) != (image_b.width(), image_b.height()) {
+        println!(
+            "dimensions differ: {}x{} vs {}x{}",
+            width,
+            height,
+            image_b.width(),
+            image_b.height()
+        );
+        return Ok(ExitCode::FAILURE);
+    }
+
+    let mut differing = 0;
+    for y in 0..height {
+        for x in 0..width {
+            let (pa, pb) = (image_a.pixel_rgb(x, y), image_b.pixel_rgb(x, y));
+            if pa != pb {
+                if differing < MAX_LISTED_DIFFS {
+                    println!("({}, {}): {:?} vs {:?}", x, y, pa, pb);
+                }
+                differing += 1;
+            }
+        }
+    }
+    if differing == 0 {
+        println!("images are identical");
+        Ok(ExitCode::SUCCESS)
+    } else {
+        println!("{} of {} pixels differ", differing, width * height);
+        Ok(ExitCode::FAILURE)
+    }
+}
@@ -0,0 +1,77 @@
+use crate::PNMImage;
+
+impl PNMImage<'_> {
+    /// Returns the bit of the pixel at the specified (x, y) coordinate of a bitmap (P4 or P1),
+    /// which is set for black pixels.
+    /// Returns `None` if the pixel is outside the bounds of the image or the image is not a bitmap.
+    pub fn pixel_bit(&self, x: usize, y: usize) -> Option<bool> {
+        if x >= self.width() {
+            return None;
+        }
+        let byte = self.row_bits(y)?[x / 8];
+        Some((byte >> (7 - x % 8)) & 1 == 1)
+    }
+
+    /// Returns the packed bits of row `y` of a bitmap (P4 or P1), most significant bit first.
+    ///
+    /// Every row is padded to a whole byte, as expected by most monochrome display drivers.
+    /// Returns `None` if the row is outside the bounds of the image, not backed by pixel data
+    /// or the image is not a bitmap.
+    pub fn row_bits(&self, y: usize) -> Option<&[u8]> {
+        if !self.is_bitmap() || y >= self.height() {
+            return None;
+        }
+        let row_len = self.row_len()?;
+        let start = y.checked_mul(row_len)?;
+        self.pixel_data().get(start..start.checked_add(row_len)?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn bitmap() {
+        // a 10 pixel wide checkerboard, every row padded to 2 bytes
+        let pbm_img = PNMImage::from_parse(b"P4\n# bits\n10 2\n\xaa\x80\x55\x40").unwrap();
+        assert_eq!(pbm_img.maximum_pixel(), 1);
+        assert_eq!(pbm_img.comment(), "# bits");
+        assert_eq!(pbm_img.pixel_bit(0, 0), Some(true));
+        assert_eq!(pbm_img.pixel_bit(9, 0), Some(false));
+        assert_eq!(pbm_img.pixel_bit(9, 1), Some(true));
+        assert_eq!(pbm_img.pixel_bit(10, 1), None);
+        assert_eq!(pbm_img.row_bits(1), Some(&[0x55, 0x40][..]));
+        assert_eq!(pbm_img.row_bits(2), None);
+
+        assert_eq!(pbm_img.pixel_rgb(0, 0), Some((0, 0, 0)));
+        assert_eq!(pbm_img.pixel_gray(1, 0), Some(255));
+        assert_eq!(pbm_img.pixel_gray16(1, 0), Some(1));
+        assert_eq!(pbm_img.validate(), Ok(()));
+
+        let huge = PNMImage::from_parse(b"P4\n100000000000 100000000000\n").unwrap();
+        assert_eq!(huge.row_bits(99999999999), None);
+        assert_eq!(huge.pixel_bit(5, 99999999999), None);
+
+        let ppm_img = PNMImage::from_parse(include_bytes!("./binary.ppm")).unwrap();
+        assert_eq!(ppm_img.pixel_bit(0, 0), None);
+    }
+
+    #[test]
+    fn plain_bitmap() {
+        let mut scratch = [0; 4];
+        let pbm_img = PNMImage::from_parse_ascii(
+            b"P1\n10 2\n1010101010\n0 1 0 1 0 1 0 1 0 1\n",
+            &mut scratch,
+        )
+        .unwrap();
+        assert_eq!(pbm_img.row_bits(0), Some(&[0xaa, 0x80][..]));
+        assert_eq!(pbm_img.row_bits(1), Some(&[0x55, 0x40][..]));
+
+        let mut out = [0; 64];
+        let len = pbm_img
+            .encode(crate::Encoding::PGMBinary, &mut out)
+            .unwrap();
+        assert_eq!(&out[..len], b"P5\n10 2\n1\n\x00\x01\x00\x01\x00\x01\x00\x01\x00\x01\x01\x00\x01\x00\x01\x00\x01\x00\x01\x00");
+    }
+}
@@ -0,0 +1,207 @@
+use crate::{BufferTooSmall, PNMImage};
+
+/// Returns the range of an image spanning `len` pixels starting at `pos` that lies within `0..fb_len`,
+/// in image coordinates.
+fn clip(pos: isize, len: usize, fb_len: usize) -> (usize, usize) {
+    let start = pos.min(0).unsigned_abs().min(len);
+    let end = (fb_len as isize).saturating_sub(pos).clamp(0, len as isize) as usize;
+    (start, end.max(start))
+}
+
+/// Blends `over` onto `under` with an opacity of `alpha` out of 255.
+fn blend(over: u8, under: u8, alpha: u8) -> u8 {
+    let (over, under, alpha) = (over as u32, under as u32, alpha as u32);
+    ((over * alpha + under * (255 - alpha) + 127) / 255) as u8
+}
+
+impl PNMImage<'_> {
+    /// Draws the image onto an 8-bit RGB framebuffer with its top left corner at (x, y)
+    ///
+    /// Parts of the image that fall outside the framebuffer are clipped, so sprites can be moved
+    /// partially off screen. Pixels of the color `key`, if given, are transparent and leave the
+    /// framebuffer unchanged, e.g. magenta for sprites without an alpha channel.
+    /// Samples are scaled from `0..=maximum_pixel` to `0..=255` before they are compared and drawn.
+    ///
+    /// # Arguments
+    ///
+    /// * `fb` - The framebuffer, three bytes per pixel in row-major order
+    /// * `fb_width` - The width of the framebuffer in pixels
+    /// * `x` - The x coordinate of the image within the framebuffer
+    /// * `y` - The y coordinate of the image within the framebuffer
+    /// * `key` - The color treated as transparent
+    pub fn blit(
+        &self,
+        fb: &mut [u8],
+        fb_width: usize,
+        x: isize,
+        y: isize,
+        key: Option<(u8, u8, u8)>,
+    ) {
+        let Some(row_len) = self.row_len() else {
+            return;
+        };
+        if fb_width == 0 || row_len == 0 {
+            return;
+        }
+        let fb_height = fb.len() / (fb_width * 3);
+        let (x0, x1) = clip(x, self.width(), fb_width);
+        let (y0, y1) = clip(y, self.height(), fb_height);
+        let triples = self.as_rgb_triples();
+
+        for (sy, row) in self
+            .pixel_data()
+            .chunks(row_len)
+            .enumerate()
+            .take(y1)
+            .skip(y0)
+        {
+            let fy = (y + sy as isize) as usize;
+            let start = (fy * fb_width + (x + x0 as isize) as usize) * 3;
+            let dst = &mut fb[start..start + (x1 - x0) * 3];
+            match (triples, key) {
+                (Some(_), None) if row.len() == row_len => {
+                    dst.copy_from_slice(&row[x0 * 3..x1 * 3]);
+                }
+                _ => {
+                    for (sx, px) in (x0..x1).zip(dst.chunks_exact_mut(3)) {
+                        let Some(color) = self.decode_at::<u8>(row, sx) else {
+                            break;
+                        };
+                        if Some(color) != key {
+                            px.copy_from_slice(&[color.0, color.1, color.2]);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Writes the image with `overlay` blended on top at (x, y) into `out`, as 8-bit RGB triples
+    ///
+    /// The overlay is drawn with a constant opacity of `alpha` out of 255 (source-over blending)
+    /// and clipped to the bounds of the image. Samples are scaled from `0..=maximum_pixel` to `0..=255`.
+    ///
+    /// # Arguments
+    ///
+    /// * `overlay` - The image to draw on top
+    /// * `x` - The x coordinate of the overlay within the image
+    /// * `y` - The y coordinate of the overlay within the image
+    /// * `alpha` - The opacity of the overlay, 255 being opaque
+    /// * `out` - A buffer of at least `width * height * 3` bytes receiving the result
+    pub fn composite(
+        &self,
+        overlay: &PNMImage<'_>,
+        x: isize,
+        y: isize,
+        alpha: u8,
+        out: &mut [u8],
+    ) -> Result<(), BufferTooSmall> {
+        self.composite_with(overlay, x, y, out, |_, _| alpha)
+    }
+
+    /// Like [`composite`](Self::composite), but takes the opacity of every overlay pixel from its alpha
+    /// channel, see [`pixel_rgba`](Self::pixel_rgba).
+    ///
+    /// An alpha equal to the maximum pixel value of the overlay is opaque. Overlays without an alpha
+    /// channel are drawn opaque.
+    pub fn composite_alpha(
+        &self,
+        overlay: &PNMImage<'_>,
+        x: isize,
+        y: isize,
+        out: &mut [u8],
+    ) -> Result<(), BufferTooSmall> {
+        self.composite_with(overlay, x, y, out, |ox, oy| {
+            let (.., a) = overlay.pixel_rgba(ox, oy).unwrap_or((0, 0, 0, 0));
+            a
+        })
+    }
+
+    fn composite_with(
+        &self,
+        overlay: &PNMImage<'_>,
+        x: isize,
+        y: isize,
+        out: &mut [u8],
+        alpha: impl Fn(usize, usize) -> u8,
+    ) -> Result<(), BufferTooSmall> {
+        let width = self.width();
+        let required = width.saturating_mul(self.height()).saturating_mul(3);
+        let out = out.get_mut(..required).ok_or(BufferTooSmall { required })?;
+        out.fill(0);
+        for (px, (r, g, b)) in out.chunks_exact_mut(3).zip(self.pixels::<u8>()) {
+            px.copy_from_slice(&[r, g, b]);
+        }
+
+        let (x0, x1) = clip(x, overlay.width(), width);
+        let (y0, y1) = clip(y, overlay.height(), self.height());
+        for oy in y0..y1 {
+            for ox in x0..x1 {
+                let Some((r, g, b)) = overlay.pixel_rgb(ox, oy) else {
+                    continue;
+                };
+                let a = alpha(ox, oy);
+                let (dx, dy) = ((x + ox as isize) as usize, (y + oy as isize) as usize);
+                let px = &mut out[(dy * width + dx) * 3..][..3];
+                for (dst, src) in px.iter_mut().zip([r, g, b]) {
+                    *dst = blend(src, *dst, a);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn blit() {
+        let sprite =
+            PNMImage::from_parse(b"P6\n2 2\n255\n\x01\x02\x03\xff\x00\xff\xff\x00\xff\x04\x05\x06")
+                .unwrap();
+        let mut fb = [0; 3 * 3 * 3];
+        sprite.blit(&mut fb, 3, 1, 1, None);
+        assert_eq!(fb[12..18], [1, 2, 3, 255, 0, 255]);
+        assert_eq!(fb[21..27], [255, 0, 255, 4, 5, 6]);
+
+        let mut fb = [9; 3 * 3 * 3];
+        sprite.blit(&mut fb, 3, -1, 1, Some((255, 0, 255)));
+        assert_eq!(fb[..18], [9; 18]);
+        // only the right column is visible, and its top pixel is transparent
+        assert_eq!(fb[18..27], [4, 5, 6, 9, 9, 9, 9, 9, 9]);
+
+        sprite.blit(&mut fb, 3, 3, 0, None);
+        sprite.blit(&mut fb, 3, -2, -2, None);
+        assert_eq!(fb[18..21], [4, 5, 6]);
+    }
+
+    #[test]
+    fn composite() {
+        let frame = PNMImage::from_parse(b"P6\n2 1\n255\n\x00\x00\x00\xc8\xc8\xc8").unwrap();
+        let bar = PNMImage::from_parse(b"P6\n1 1\n255\n\xff\x00\x00").unwrap();
+        let mut out = [0; 6];
+        frame.composite(&bar, 1, 0, 128, &mut out).unwrap();
+        assert_eq!(out, [0, 0, 0, 228, 100, 100]);
+        assert_eq!(
+            frame.composite(&bar, 0, 0, 128, &mut out[..3]),
+            Err(BufferTooSmall { required: 6 })
+        );
+
+        let icon = PNMImage::from_parse(
+            b"P7\nWIDTH 2\nHEIGHT 1\nDEPTH 4\nMAXVAL 255\nTUPLTYPE RGB_ALPHA\nENDHDR\n\xff\xff\xff\xff\xff\xff\xff\x00",
+        )
+        .unwrap();
+        frame.composite_alpha(&icon, 0, 0, &mut out).unwrap();
+        assert_eq!(out, [255, 255, 255, 200, 200, 200]);
+    }
+
+    #[test]
+    fn blit_gray() {
+        let sprite = PNMImage::from_parse(b"P5\n2 1\n255\n\x10\x20").unwrap();
+        let mut fb = [0; 2 * 3];
+        sprite.blit(&mut fb, 2, 0, 0, Some((0x20, 0x20, 0x20)));
+        assert_eq!(fb, [0x10, 0x10, 0x10, 0, 0, 0]);
+    }
+}
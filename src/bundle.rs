@@ -0,0 +1,311 @@
+//! A simple container for shipping many named PNM images as a single asset, e.g. in firmware.
+//!
+//! A bundle starts with the magic bytes `PNMB` and the number of images as a little-endian `u32`,
+//! followed by an index entry per image and the concatenated PNM files. Every index entry holds
+//! the length of the name as a `u8`, the UTF-8 encoded name, and the offset of the file from the
+//! start of the bundle and its length, both as little-endian `u32`. The index is sorted by name,
+//! so duplicate names are found by comparing neighbouring entries.
+//!
+//! [`Bundle`] reads bundles without allocating, while the `alloc` feature adds [`BundleWriter`]
+//! for creating them, e.g. from a build script:
+//!
+//! ```no_run
+//! # #[cfg(feature = "alloc")] {
+//! use micropnm::bundle::{Bundle, BundleWriter};
+//!
+//! let mut writer = BundleWriter::new();
+//! writer.add("logo", &std::fs::read("assets/logo.ppm").unwrap()).unwrap();
+//! let out_dir = std::env::var("OUT_DIR").unwrap();
+//! std::fs::write(format!("{}/assets.bin", out_dir), writer.finish()).unwrap();
+//!
+//! // the firmware then reads the bundle, e.g. from `include_bytes!`
+//! let data = std::fs::read(format!("{}/assets.bin", out_dir)).unwrap();
+//! let bundle = Bundle::parse(&data).unwrap();
+//! let logo = bundle.get("logo").unwrap();
+//! # }
+//! ```
+
+use crate::{PNMError, PNMImage};
+
+/// The magic bytes every bundle starts with
+const MAGIC: &[u8; 4] = b"PNMB";
+
+/// Error type that represents the ways a bundle can be invalid
+#[derive(Debug, PartialEq, Eq)]
+pub enum BundleError {
+    /// The data does not start with the magic bytes of a bundle
+    InvalidMagic,
+    /// The index or an image lies beyond the end of the data
+    Truncated,
+    /// A name is not valid UTF-8, longer than 255 bytes, given more than once or out of order
+    InvalidName,
+    /// An image is not a valid binary PNM image
+    Image(PNMError),
+}
+
+impl From<PNMError> for BundleError {
+    fn from(err: PNMError) -> Self {
+        BundleError::Image(err)
+    }
+}
+
+/// A bundle of named PNM images borrowing its data, e.g. from `include_bytes!`
+#[derive(Clone, Copy, Debug)]
+pub struct Bundle<'a> {
+    data: &'a [u8],
+    len: usize,
+}
+
+impl<'a> Bundle<'a> {
+    /// Parses the index of a bundle and checks that all of its images can be parsed.
+    pub fn parse(data: &'a [u8]) -> Result<Self, BundleError> {
+        if data.get(..4) != Some(&MAGIC[..]) {
+            return Err(BundleError::InvalidMagic);
+        }
+        let len = read_u32(data, 4)? as usize;
+        let bundle = Bundle { data, len };
+        let mut previous = None;
+        for entry in bundle.entries_raw().take(len) {
+            let (name, offset, length) = entry?;
+            // names are sorted, so a duplicate directly follows the first occurrence
+            if previous.is_some_and(|previous| previous >= name) {
+                return Err(BundleError::InvalidName);
+            }
+            previous = Some(name);
+            bundle.image(offset, length)?;
+        }
+        Ok(bundle)
+    }
+
+    /// Returns the number of images in the bundle.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether the bundle holds no images.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the image with the given name, or `None` if the bundle holds no such image.
+    ///
+    /// Only the index is searched, so just the image found is parsed.
+    pub fn get(&self, name: &str) -> Option<PNMImage<'a>> {
+        for entry in self.entries_raw().take(self.len) {
+            let (n, offset, length) = entry.ok()?;
+            if n == name {
+                return self.image(offset, length).ok();
+            }
+            if n > name {
+                // the index is sorted, so the name cannot follow
+                break;
+            }
+        }
+        None
+    }
+
+    /// Returns an iterator over the names and images of the bundle, in the order of its index.
+    pub fn iter(&self) -> impl Iterator<Item = Result<(&'a str, PNMImage<'a>), BundleError>> + 'a {
+        let bundle = *self;
+        self.entries_raw().take(self.len).map(move |entry| {
+            let (name, offset, length) = entry?;
+            Ok((name, bundle.image(offset, length)?))
+        })
+    }
+
+    /// Parses the image of `length` bytes at `offset`.
+    fn image(&self, offset: usize, length: usize) -> Result<PNMImage<'a>, BundleError> {
+        let bytes = self
+            .data
+            .get(offset..)
+            .and_then(|rest| rest.get(..length))
+            .ok_or(BundleError::Truncated)?;
+        Ok(PNMImage::from_parse(bytes)?)
+    }
+
+    /// Returns an iterator over the index entries as name, offset and length.
+    fn entries_raw(
+        &self,
+    ) -> impl Iterator<Item = Result<(&'a str, usize, usize), BundleError>> + 'a {
+        let data = self.data;
+        let mut pos = 8;
+        core::iter::from_fn(move || {
+            let entry = (|| {
+                let name_len = *data.get(pos).ok_or(BundleError::Truncated)? as usize;
+                let name = data
+                    .get(pos + 1..pos + 1 + name_len)
+                    .ok_or(BundleError::Truncated)?;
+                let name = core::str::from_utf8(name).map_err(|_| BundleError::InvalidName)?;
+                let offset = read_u32(data, pos + 1 + name_len)? as usize;
+                let length = read_u32(data, pos + 5 + name_len)? as usize;
+                pos += 9 + name_len;
+                Ok((name, offset, length))
+            })();
+            Some(entry)
+        })
+    }
+}
+
+/// Reads a little-endian `u32` at `pos`.
+fn read_u32(data: &[u8], pos: usize) -> Result<u32, BundleError> {
+    let bytes = data.get(pos..pos + 4).ok_or(BundleError::Truncated)?;
+    Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+/// Creates bundles from PNM files, see the [module documentation](self) for the layout
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, Default)]
+pub struct BundleWriter {
+    entries: alloc::vec::Vec<(alloc::string::String, alloc::vec::Vec<u8>)>,
+}
+
+#[cfg(feature = "alloc")]
+impl BundleWriter {
+    /// Creates a writer for an empty bundle.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a binary PNM file under `name`, checking that it can be parsed.
+    ///
+    /// Images are kept sorted by name, as required by the index.
+    pub fn add(&mut self, name: &str, bytes: &[u8]) -> Result<&mut Self, BundleError> {
+        if name.len() > 255 {
+            return Err(BundleError::InvalidName);
+        }
+        let idx = match self.entries.binary_search_by(|(n, _)| n.as_str().cmp(name)) {
+            Ok(_) => return Err(BundleError::InvalidName),
+            Err(idx) => idx,
+        };
+        PNMImage::from_parse(bytes)?;
+        self.entries.insert(idx, (name.into(), bytes.into()));
+        Ok(self)
+    }
+
+    /// Returns the bytes of the bundle holding all images added so far.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the bundle exceeds 4 GiB.
+    pub fn finish(&self) -> alloc::vec::Vec<u8> {
+        let to_u32 = |value: usize| u32::try_from(value).expect("bundle exceeds 4 GiB");
+        let index_len: usize = self.entries.iter().map(|(name, _)| 9 + name.len()).sum();
+        let mut offset = 8 + index_len;
+        let mut out = alloc::vec::Vec::with_capacity(
+            offset
+                + self
+                    .entries
+                    .iter()
+                    .map(|(_, bytes)| bytes.len())
+                    .sum::<usize>(),
+        );
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&to_u32(self.entries.len()).to_le_bytes());
+        for (name, bytes) in &self.entries {
+            out.push(name.len() as u8);
+            out.extend_from_slice(name.as_bytes());
+            out.extend_from_slice(&to_u32(offset).to_le_bytes());
+            out.extend_from_slice(&to_u32(bytes.len()).to_le_bytes());
+            offset += bytes.len();
+        }
+        for (_, bytes) in &self.entries {
+            out.extend_from_slice(bytes);
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// The length of the test bundle: the header, two index entries, a 1x1 image and the test image
+    const LEN: usize = 8 + 13 + 13 + 12 + 12347;
+
+    /// A bundle holding a 1x1 gray image named `icon` and the test image named `logo`
+    fn bundle() -> [u8; LEN] {
+        let raw_img = include_bytes!("./binary.ppm");
+        let icon = b"P5\n1 1\n255\n\x80";
+        let mut out = [0; LEN];
+        let mut pos = 0;
+        let mut put = |bytes: &[u8]| {
+            out[pos..pos + bytes.len()].copy_from_slice(bytes);
+            pos += bytes.len();
+        };
+        put(b"PNMB\x02\x00\x00\x00");
+        put(b"\x04icon");
+        put(&34u32.to_le_bytes());
+        put(&(icon.len() as u32).to_le_bytes());
+        put(b"\x04logo");
+        put(&(34 + icon.len() as u32).to_le_bytes());
+        put(&(raw_img.len() as u32).to_le_bytes());
+        put(icon);
+        put(raw_img);
+        out
+    }
+
+    #[test]
+    fn read_bundle() {
+        let data = bundle();
+        let bundle = Bundle::parse(&data).unwrap();
+        assert_eq!(bundle.len(), 2);
+        assert_eq!(bundle.get("icon").unwrap().pixel_gray(0, 0), Some(0x80));
+        assert_eq!(
+            bundle.get("logo").unwrap().pixel_rgb(56, 56),
+            Some((0, 0, 255))
+        );
+        assert!(bundle.get("missing").is_none());
+        let mut names = bundle.iter().map(|entry| entry.map(|(name, _)| name));
+        assert_eq!(
+            (names.next(), names.next(), names.next()),
+            (Some(Ok("icon")), Some(Ok("logo")), None)
+        );
+
+        assert_eq!(
+            Bundle::parse(b"PNMA").unwrap_err(),
+            BundleError::InvalidMagic
+        );
+        assert_eq!(
+            Bundle::parse(&data[..100]).unwrap_err(),
+            BundleError::Truncated
+        );
+        let mut broken = data;
+        broken[34] = b'X';
+        assert_eq!(
+            Bundle::parse(&broken).unwrap_err(),
+            BundleError::Image(PNMError::NotPNMFormat)
+        );
+        let mut duplicate = data;
+        duplicate[22..26].copy_from_slice(b"icon");
+        assert_eq!(
+            Bundle::parse(&duplicate).unwrap_err(),
+            BundleError::InvalidName
+        );
+        let mut unsorted = data;
+        unsorted[22..26].copy_from_slice(b"band");
+        assert_eq!(
+            Bundle::parse(&unsorted).unwrap_err(),
+            BundleError::InvalidName
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn write_bundle() {
+        let mut writer = BundleWriter::new();
+        writer
+            .add("logo", include_bytes!("./binary.ppm"))
+            .unwrap()
+            .add("icon", b"P5\n1 1\n255\n\x80")
+            .unwrap();
+        assert_eq!(writer.finish(), bundle());
+        assert_eq!(
+            writer.add("icon", b"P5\n1 1\n255\n\x80").unwrap_err(),
+            BundleError::InvalidName
+        );
+        assert_eq!(
+            writer.add("bad", b"P3\n").unwrap_err(),
+            BundleError::Image(PNMError::UnsupportedPNMFormat)
+        );
+    }
+}
@@ -0,0 +1,74 @@
+use crate::PNMImage;
+
+/// The algorithms [`PNMImage::checksum`] can compute
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Checksum {
+    /// CRC-32 as used by zlib and PNG
+    Crc32,
+    /// 32-bit FNV-1a, which needs no lookup table
+    Fnv1a,
+}
+
+/// Lookup table for the reflected CRC-32 polynomial, built at compile time
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0, |crc, &b| {
+        CRC32_TABLE[((crc ^ b as u32) & 0xFF) as usize] ^ (crc >> 8)
+    })
+}
+
+fn fnv1a(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0x811C_9DC5, |hash, &b| {
+        (hash ^ b as u32).wrapping_mul(0x0100_0193)
+    })
+}
+
+impl PNMImage<'_> {
+    /// Computes a checksum over the pixel data, e.g. to detect corrupted assets
+    /// or to compare against golden values in tests.
+    ///
+    /// Only the pixel data described by the header is hashed, so comments and trailing bytes
+    /// do not change the checksum.
+    pub fn checksum(&self, algorithm: Checksum) -> u32 {
+        let raster = self.raster();
+        match algorithm {
+            Checksum::Crc32 => crc32(raster),
+            Checksum::Fnv1a => fnv1a(raster),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn checksums() {
+        let ppm_img = PNMImage::from_parse(b"P6\n# check\n3 1\n255\n123456789\n").unwrap();
+        assert_eq!(ppm_img.checksum(Checksum::Crc32), 0xCBF4_3926);
+        assert_eq!(ppm_img.checksum(Checksum::Fnv1a), 0xBB86_B11C);
+
+        let empty = PNMImage::from_parse(b"P6\n0 0\n255\n").unwrap();
+        assert_eq!(empty.checksum(Checksum::Crc32), 0);
+        assert_eq!(empty.checksum(Checksum::Fnv1a), 0x811C_9DC5);
+    }
+}
@@ -0,0 +1,184 @@
+//! Build-time conversion of images into Rust source, for firmware that embeds its assets.
+//!
+//! Call [`generate`] from a build script and write the result to a file in `OUT_DIR`,
+//! which the firmware then pulls in with `include!`:
+//!
+//! ```no_run
+//! use micropnm::codegen::{generate, TargetFormat};
+//! use micropnm::PNMImage;
+//!
+//! let bytes = std::fs::read("assets/logo.ppm").unwrap();
+//! let image = PNMImage::from_parse(&bytes).unwrap();
+//! let source = generate(&image, "LOGO", TargetFormat::Rgb565).unwrap();
+//! let out_dir = std::env::var("OUT_DIR").unwrap();
+//! std::fs::write(format!("{}/logo.rs", out_dir), source).unwrap();
+//! ```
+
+use std::fmt::Write;
+use std::string::String;
+use std::vec;
+
+use crate::{PNMImage, PixelFormat};
+
+/// Error type that represents the different code generation errors
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CodegenError {
+    /// The name is not a valid Rust identifier
+    InvalidName,
+    /// The converted image does not fit into memory
+    TooLarge,
+}
+
+/// Words that cannot be used as identifiers
+const KEYWORDS: [&str; 39] = [
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "gen", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut",
+    "pub", "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type",
+    "unsafe", "use", "where", "while",
+];
+
+/// Returns whether `name` is a valid Rust identifier.
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && name != "_"
+        && !KEYWORDS.contains(&name)
+}
+
+/// The pixel formats images can be converted to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TargetFormat {
+    /// One `u16` per pixel with 5 bits of red, 6 bits of green and 5 bits of blue
+    Rgb565,
+    /// Three `u8` per pixel
+    Rgb888,
+    /// One bit per pixel, most significant bit first, with every row padded to a whole byte.
+    /// A bit is set for pixels brighter than half the maximum pixel value.
+    Mono,
+}
+
+/// Generates Rust source defining the image as a `static` array named `name`,
+/// along with `<name>_WIDTH` and `<name>_HEIGHT` constants.
+///
+/// The pixels are converted with [`PNMImage::decode_into`], so samples are scaled from
+/// `0..=maximum_pixel` to `0..=255`. Pixels missing from the pixel data are emitted as black.
+///
+/// # Errors
+///
+/// Returns [`CodegenError::InvalidName`] if `name` is not a valid Rust identifier
+/// and [`CodegenError::TooLarge`] if the converted pixels do not fit into memory.
+pub fn generate(
+    image: &PNMImage<'_>,
+    name: &str,
+    format: TargetFormat,
+) -> Result<String, CodegenError> {
+    if !is_identifier(name) {
+        return Err(CodegenError::InvalidName);
+    }
+    let (width, height) = (image.width(), image.height());
+    let pixel_format = match format {
+        TargetFormat::Rgb565 => PixelFormat::Rgb565Le,
+        TargetFormat::Rgb888 => PixelFormat::Rgb888,
+        TargetFormat::Mono => PixelFormat::Mono1,
+    };
+    let len = pixel_format.buffer_len(width, height);
+    if len == usize::MAX {
+        return Err(CodegenError::TooLarge);
+    }
+    let mut pixels = vec![0; len];
+    image
+        .decode_into(pixel_format, &mut pixels)
+        .map_err(|_| CodegenError::TooLarge)?;
+
+    let mut src = String::new();
+    // writing to a String cannot fail
+    let _ = writeln!(src, "pub const {}_WIDTH: usize = {};", name, width);
+    let _ = writeln!(src, "pub const {}_HEIGHT: usize = {};", name, height);
+    match format {
+        TargetFormat::Rgb565 => {
+            let values = pixels
+                .chunks_exact(2)
+                .map(|px| format!("{:#06x}", u16::from_le_bytes([px[0], px[1]])));
+            write_array(&mut src, name, "u16", len / 2, 8, values);
+        }
+        TargetFormat::Rgb888 | TargetFormat::Mono => {
+            let values = pixels.iter().map(|v| format!("{:#04x}", v));
+            write_array(&mut src, name, "u8", len, 12, values);
+        }
+    }
+    Ok(src)
+}
+
+/// Writes a `static` array definition with `per_line` values per line.
+fn write_array(
+    src: &mut String,
+    name: &str,
+    ty: &str,
+    len: usize,
+    per_line: usize,
+    values: impl Iterator<Item = String>,
+) {
+    let _ = writeln!(src, "pub static {}: [{}; {}] = [", name, ty, len);
+    for (i, value) in values.enumerate() {
+        if i % per_line == 0 {
+            if i > 0 {
+                src.push('\n');
+            }
+            src.push_str("    ");
+        } else {
+            src.push(' ');
+        }
+        src.push_str(&value);
+        src.push(',');
+    }
+    if len > 0 {
+        src.push('\n');
+    }
+    src.push_str("];\n");
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn codegen() {
+        let ppm_img =
+            PNMImage::from_parse(b"P6\n3 1\n255\n\xff\x00\x00\x00\xff\x00\xff\xff\xff").unwrap();
+        assert_eq!(
+            generate(&ppm_img, "DOT", TargetFormat::Rgb565).unwrap(),
+            "pub const DOT_WIDTH: usize = 3;\n\
+             pub const DOT_HEIGHT: usize = 1;\n\
+             pub static DOT: [u16; 3] = [\n    0xf800, 0x07e0, 0xffff,\n];\n"
+        );
+        assert!(generate(&ppm_img, "DOT", TargetFormat::Rgb888)
+            .unwrap()
+            .ends_with(
+                "[u8; 9] = [\n    0xff, 0x00, 0x00, 0x00, 0xff, 0x00, 0xff, 0xff, 0xff,\n];\n"
+            ));
+        assert!(generate(&ppm_img, "DOT", TargetFormat::Mono)
+            .unwrap()
+            .ends_with("[u8; 1] = [\n    0x60,\n];\n"));
+
+        // samples are scaled, so maximum_pixel is white
+        let pgm_img = PNMImage::from_parse(b"P5\n2 1\n15\n\x0f\x00").unwrap();
+        assert!(generate(&pgm_img, "DOT", TargetFormat::Rgb888)
+            .unwrap()
+            .ends_with("[u8; 6] = [\n    0xff, 0xff, 0xff, 0x00, 0x00, 0x00,\n];\n"));
+        let pbm_img = PNMImage::from_parse(b"P4\n2 1\n\x40").unwrap();
+        assert!(generate(&pbm_img, "DOT", TargetFormat::Rgb565)
+            .unwrap()
+            .ends_with("[u16; 2] = [\n    0xffff, 0x0000,\n];\n"));
+
+        for name in ["", "_", "1DOT", "DOT-1", "fn", "DÖT"] {
+            assert_eq!(
+                generate(&ppm_img, name, TargetFormat::Rgb888),
+                Err(CodegenError::InvalidName)
+            );
+        }
+        assert!(generate(&ppm_img, "_DOT_1", TargetFormat::Rgb888).is_ok());
+    }
+}
@@ -0,0 +1,248 @@
+//! Color space conversions: between sRGB-encoded samples and linear light, and to YCbCr and HSV.
+//!
+//! PPM files are usually sRGB encoded, so blending or averaging raw samples gives visibly
+//! wrong results. The helpers here decode samples into 16-bit linear values through a lookup
+//! table, which keeps them usable on `no_std` targets without floating point support.
+//! The YCbCr and HSV conversions use fixed-point integer math for the same reason.
+
+use crate::{BufferTooSmall, PNMImage};
+
+/// Linear light values, scaled to `0..=65535`, for every 8-bit sRGB sample
+#[rustfmt::skip]
+const SRGB_TO_LINEAR: [u16; 256] = [
+    0, 20, 40, 60, 80, 99, 119, 139,
+    159, 179, 199, 219, 241, 264, 288, 313,
+    340, 367, 396, 427, 458, 491, 526, 562,
+    599, 637, 677, 718, 761, 805, 851, 898,
+    947, 997, 1048, 1101, 1156, 1212, 1270, 1330,
+    1391, 1453, 1517, 1583, 1651, 1720, 1790, 1863,
+    1937, 2013, 2090, 2170, 2250, 2333, 2418, 2504,
+    2592, 2681, 2773, 2866, 2961, 3058, 3157, 3258,
+    3360, 3464, 3570, 3678, 3788, 3900, 4014, 4129,
+    4247, 4366, 4488, 4611, 4736, 4864, 4993, 5124,
+    5257, 5392, 5530, 5669, 5810, 5953, 6099, 6246,
+    6395, 6547, 6700, 6856, 7014, 7174, 7335, 7500,
+    7666, 7834, 8004, 8177, 8352, 8528, 8708, 8889,
+    9072, 9258, 9445, 9635, 9828, 10022, 10219, 10417,
+    10619, 10822, 11028, 11235, 11446, 11658, 11873, 12090,
+    12309, 12530, 12754, 12980, 13209, 13440, 13673, 13909,
+    14146, 14387, 14629, 14874, 15122, 15371, 15623, 15878,
+    16135, 16394, 16656, 16920, 17187, 17456, 17727, 18001,
+    18277, 18556, 18837, 19121, 19407, 19696, 19987, 20281,
+    20577, 20876, 21177, 21481, 21787, 22096, 22407, 22721,
+    23038, 23357, 23678, 24002, 24329, 24658, 24990, 25325,
+    25662, 26001, 26344, 26688, 27036, 27386, 27739, 28094,
+    28452, 28813, 29176, 29542, 29911, 30282, 30656, 31033,
+    31412, 31794, 32179, 32567, 32957, 33350, 33745, 34143,
+    34544, 34948, 35355, 35764, 36176, 36591, 37008, 37429,
+    37852, 38278, 38706, 39138, 39572, 40009, 40449, 40891,
+    41337, 41785, 42236, 42690, 43147, 43606, 44069, 44534,
+    45002, 45473, 45947, 46423, 46903, 47385, 47871, 48359,
+    48850, 49344, 49841, 50341, 50844, 51349, 51858, 52369,
+    52884, 53401, 53921, 54445, 54971, 55500, 56032, 56567,
+    57105, 57646, 58190, 58737, 59287, 59840, 60396, 60955,
+    61517, 62082, 62650, 63221, 63795, 64372, 64952, 65535,
+];
+
+/// Converts an 8-bit sRGB sample to a linear light value in `0..=65535`.
+pub fn srgb_to_linear(value: u8) -> u16 {
+    SRGB_TO_LINEAR[value as usize]
+}
+
+/// Converts a linear light value in `0..=65535` back to the nearest 8-bit sRGB sample.
+pub fn linear_to_srgb(value: u16) -> u8 {
+    match SRGB_TO_LINEAR.binary_search(&value) {
+        Ok(idx) => idx as u8,
+        Err(0) => 0,
+        Err(idx) => {
+            // value lies between two table entries, pick the closer one
+            if value - SRGB_TO_LINEAR[idx - 1] <= SRGB_TO_LINEAR[idx] - value {
+                (idx - 1) as u8
+            } else {
+                idx as u8
+            }
+        }
+    }
+}
+
+/// Converts sRGB samples to linear light values.
+///
+/// Converts as many samples as fit in the shorter of the two slices.
+pub fn srgb_to_linear_slice(src: &[u8], dst: &mut [u16]) {
+    for (s, d) in src.iter().zip(dst.iter_mut()) {
+        *d = srgb_to_linear(*s);
+    }
+}
+
+/// Converts linear light values to sRGB samples.
+///
+/// Converts as many samples as fit in the shorter of the two slices.
+pub fn linear_to_srgb_slice(src: &[u16], dst: &mut [u8]) {
+    for (s, d) in src.iter().zip(dst.iter_mut()) {
+        *d = linear_to_srgb(*s);
+    }
+}
+
+/// Converts 8-bit RGB samples to full-range YCbCr as used by JPEG (JFIF).
+pub fn rgb_to_ycbcr(r: u8, g: u8, b: u8) -> (u8, u8, u8) {
+    let (r, g, b) = (r as i32, g as i32, b as i32);
+    // BT.601 weights in 16.16 fixed point
+    let y = 19595 * r + 38470 * g + 7471 * b;
+    let cb = -11059 * r - 21709 * g + 32768 * b + (128 << 16);
+    let cr = 32768 * r - 27439 * g - 5329 * b + (128 << 16);
+    let round = |v: i32| ((v + (1 << 15)) >> 16).clamp(0, 255) as u8;
+    (round(y), round(cb), round(cr))
+}
+
+/// Converts 8-bit RGB samples to HSV.
+///
+/// The hue is given in degrees in `0..360`, saturation and value in `0..=255`.
+/// The hue of grays is 0.
+pub fn rgb_to_hsv(r: u8, g: u8, b: u8) -> (u16, u8, u8) {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let delta = (max - min) as i32;
+    if delta == 0 {
+        return (0, 0, max);
+    }
+    let saturation = (delta * 255 + max as i32 / 2) / max as i32;
+    let (r, g, b) = (r as i32, g as i32, b as i32);
+    let (base, diff) = if max as i32 == r {
+        (0, g - b)
+    } else if max as i32 == g {
+        (120, b - r)
+    } else {
+        (240, r - g)
+    };
+    // round to the nearest degree
+    let hue = base + (60 * diff * 2 + delta * diff.signum()) / (2 * delta);
+    (hue.rem_euclid(360) as u16, saturation as u8, max)
+}
+
+impl PNMImage<'_> {
+    /// Returns the YCbCr values of the pixel at the specified (x, y) coordinate, see [`rgb_to_ycbcr`].
+    /// Returns `None` if the pixel is outside the bounds of the image.
+    pub fn pixel_ycbcr(&self, x: usize, y: usize) -> Option<(u8, u8, u8)> {
+        let (r, g, b) = self.pixel_rgb(x, y)?;
+        Some(rgb_to_ycbcr(r, g, b))
+    }
+
+    /// Returns the HSV values of the pixel at the specified (x, y) coordinate, see [`rgb_to_hsv`].
+    /// Returns `None` if the pixel is outside the bounds of the image.
+    pub fn pixel_hsv(&self, x: usize, y: usize) -> Option<(u16, u8, u8)> {
+        let (r, g, b) = self.pixel_rgb(x, y)?;
+        Some(rgb_to_hsv(r, g, b))
+    }
+
+    /// Writes the YCbCr values of all pixels into `out`, three bytes per pixel in row-major order.
+    ///
+    /// `out` must hold at least `width * height * 3` bytes.
+    pub fn to_ycbcr(&self, out: &mut [u8]) -> Result<(), BufferTooSmall> {
+        let required = self.width().saturating_mul(self.height()).saturating_mul(3);
+        if out.len() < required {
+            return Err(BufferTooSmall { required });
+        }
+        for (px, (r, g, b)) in out[..required].chunks_exact_mut(3).zip(self.pixels::<u8>()) {
+            let (y, cb, cr) = rgb_to_ycbcr(r, g, b);
+            px.copy_from_slice(&[y, cb, cr]);
+        }
+        Ok(())
+    }
+
+    /// Writes the HSV values of all pixels into `out`, one entry per pixel in row-major order.
+    ///
+    /// `out` must hold at least `width * height` entries.
+    pub fn to_hsv(&self, out: &mut [(u16, u8, u8)]) -> Result<(), BufferTooSmall> {
+        let required = self.width().saturating_mul(self.height());
+        if out.len() < required {
+            return Err(BufferTooSmall { required });
+        }
+        for (hsv, (r, g, b)) in out.iter_mut().zip(self.pixels::<u8>()) {
+            *hsv = rgb_to_hsv(r, g, b);
+        }
+        Ok(())
+    }
+
+    /// Returns the linear light RGB values of the pixel at the specified (x, y) coordinate.
+    /// Returns `None` if the pixel is outside the bounds of the image.
+    pub fn pixel_rgb_linear(&self, x: usize, y: usize) -> Option<(u16, u16, u16)> {
+        let (r, g, b) = self.pixel_rgb(x, y)?;
+        Some((srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b)))
+    }
+
+    /// Writes the linear light values of all pixels into `out`, three samples per pixel in row-major order.
+    ///
+    /// `out` must hold at least `width * height * 3` samples.
+    pub fn to_linear(&self, out: &mut [u16]) -> Result<(), BufferTooSmall> {
+        let required = self.width().saturating_mul(self.height()).saturating_mul(3);
+        if out.len() < required {
+            return Err(BufferTooSmall { required });
+        }
+        if let Some(data) = self.as_rgb_triples() {
+            srgb_to_linear_slice(data.as_flattened(), out);
+            return Ok(());
+        }
+        for (px, (r, g, b)) in out[..required].chunks_exact_mut(3).zip(self.pixels::<u8>()) {
+            px.copy_from_slice(&[srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b)]);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn srgb_roundtrip() {
+        assert_eq!(srgb_to_linear(0), 0);
+        assert_eq!(srgb_to_linear(255), 65535);
+        for v in 0..=255 {
+            assert_eq!(linear_to_srgb(srgb_to_linear(v)), v);
+        }
+        // averaging black and white in linear light lands well above sRGB 128
+        assert_eq!(linear_to_srgb(65535 / 2), 188);
+    }
+
+    #[test]
+    fn ycbcr_and_hsv() {
+        assert_eq!(rgb_to_ycbcr(0, 0, 0), (0, 128, 128));
+        assert_eq!(rgb_to_ycbcr(255, 255, 255), (255, 128, 128));
+        assert_eq!(rgb_to_ycbcr(255, 0, 0), (76, 85, 255));
+
+        assert_eq!(rgb_to_hsv(255, 0, 0), (0, 255, 255));
+        assert_eq!(rgb_to_hsv(255, 255, 0), (60, 255, 255));
+        assert_eq!(rgb_to_hsv(0, 128, 128), (180, 255, 128));
+        assert_eq!(rgb_to_hsv(255, 0, 128), (330, 255, 255));
+        assert_eq!(rgb_to_hsv(100, 100, 100), (0, 0, 100));
+
+        let raw_img = include_bytes!("./binary.ppm");
+        let ppm_img = PNMImage::from_parse(raw_img).unwrap();
+        assert_eq!(ppm_img.pixel_hsv(56, 56), Some((240, 255, 255)));
+        let mut hsv = [(0, 0, 0); 64 * 64];
+        ppm_img.to_hsv(&mut hsv).unwrap();
+        assert_eq!(hsv[31 * 64 + 31], (0, 0, 255));
+        let mut ycbcr = [0; 64 * 64 * 3];
+        ppm_img.to_ycbcr(&mut ycbcr).unwrap();
+        assert_eq!(ycbcr[(7 * 64 + 31) * 3..][..3], [76, 85, 255]);
+    }
+
+    #[test]
+    fn linear_pixels() {
+        let raw_img = include_bytes!("./binary.ppm");
+        let ppm_img = PNMImage::from_parse(raw_img).unwrap();
+
+        assert_eq!(
+            ppm_img.pixel_rgb_linear(31, 31),
+            Some((65535, 65535, 65535))
+        );
+        assert_eq!(ppm_img.pixel_rgb_linear(31, 7), Some((65535, 0, 0)));
+        assert_eq!(ppm_img.pixel_rgb_linear(64, 63), None);
+
+        let mut out = [0; 64 * 64 * 3];
+        ppm_img.to_linear(&mut out).unwrap();
+        let idx = (31 * 64 + 31) * 3;
+        assert_eq!(out[idx..idx + 3], [65535, 65535, 65535]);
+        assert!(ppm_img.to_linear(&mut out[..3]).is_err());
+    }
+}
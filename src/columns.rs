@@ -0,0 +1,61 @@
+use crate::PNMImage;
+
+impl PNMImage<'_> {
+    /// Returns an iterator over the RGB values of the pixels in column `x`, from top to bottom.
+    /// Returns `None` if the column is outside the bounds of the image.
+    ///
+    /// The iterator ends early if the image holds less pixel data than its header describes.
+    /// Samples are scaled from `0..=maximum_pixel` to `0..=255`.
+    pub fn column(&self, x: usize) -> Option<impl Iterator<Item = (u8, u8, u8)> + '_> {
+        if x >= self.width() {
+            return None;
+        }
+        let pixels = self
+            .pixel_data()
+            .chunks(self.row_len()?)
+            .take(self.height())
+            .map_while(move |row| self.decode_at(row, x));
+        Some(pixels)
+    }
+
+    /// Returns an iterator over all columns of the image, from left to right.
+    /// See [`column`](Self::column).
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = (u8, u8, u8)> + '_> + '_ {
+        (0..self.width()).filter_map(move |x| self.column(x))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn columns() {
+        let raw_img = include_bytes!("./binary.ppm");
+        let ppm_img = PNMImage::from_parse(raw_img).unwrap();
+
+        let mut column = [(0, 0, 0); 64];
+        for (dst, px) in column.iter_mut().zip(ppm_img.column(31).unwrap()) {
+            *dst = px;
+        }
+        assert_eq!(ppm_img.column(31).unwrap().count(), 64);
+        assert_eq!(column[7], (255, 0, 0));
+        assert_eq!(column[31], (255, 255, 255));
+        assert!(column
+            .iter()
+            .enumerate()
+            .all(|(y, &px)| Some(px) == ppm_img.pixel_rgb(31, y)));
+        assert!(ppm_img.column(64).is_none());
+
+        assert_eq!(ppm_img.columns().count(), 64);
+        assert_eq!(ppm_img.columns().nth(56).unwrap().last(), Some((0, 0, 255)));
+    }
+
+    #[test]
+    fn short_column() {
+        let ppm_img =
+            PNMImage::from_parse(b"P6\n2 2\n255\n\x01\x02\x03\x04\x05\x06\x07\x08\x09").unwrap();
+        assert_eq!(ppm_img.column(0).unwrap().count(), 2);
+        assert_eq!(ppm_img.column(1).unwrap().count(), 1);
+    }
+}
@@ -0,0 +1,80 @@
+use alloc::borrow::Cow;
+
+use crate::{PNMHeader, PNMImage};
+
+/// A PNM image that borrows its pixel data until it is first modified, created by [`PNMImage::into_cow`]
+#[derive(Clone, Debug)]
+pub struct CowPNMImage<'a> {
+    /// The image holding the header, its pixel data is replaced by `pixel_data`
+    image: PNMImage<'a>,
+    pixel_data: Cow<'a, [u8]>,
+}
+
+impl<'a> PNMImage<'a> {
+    /// Wraps the image so its pixel data is only copied once it is modified.
+    ///
+    /// Trailing bytes after the pixel data described by the header are dropped.
+    pub fn into_cow(self) -> CowPNMImage<'a> {
+        let pixel_data: &'a [u8] = match self {
+            PNMImage::PPMBinary { pixel_data, .. }
+            | PNMImage::PGMBinary { pixel_data, .. }
+            | PNMImage::PBMBinary { pixel_data, .. }
+            | PNMImage::PAM { pixel_data, .. } => pixel_data,
+        };
+        let len = self.raster().len();
+        CowPNMImage {
+            image: self,
+            pixel_data: Cow::Borrowed(&pixel_data[..len]),
+        }
+    }
+}
+
+impl<'a> CowPNMImage<'a> {
+    /// Returns the header of the image.
+    pub fn header(&self) -> PNMHeader<'a> {
+        self.image.header()
+    }
+
+    /// Returns a read-only view of the image, giving access to all accessors of [`PNMImage`].
+    pub fn as_image(&self) -> PNMImage<'_> {
+        let mut image = self.image.clone();
+        image.set_pixel_data(&self.pixel_data);
+        image
+    }
+
+    /// Returns whether the pixel data is still borrowed, i.e. has not been modified.
+    pub fn is_borrowed(&self) -> bool {
+        matches!(self.pixel_data, Cow::Borrowed(_))
+    }
+
+    /// Returns the raw pixel data of the image, copying it on the first call.
+    pub fn pixel_data_mut(&mut self) -> &mut [u8] {
+        self.pixel_data.to_mut()
+    }
+}
+
+impl<'a> From<PNMImage<'a>> for CowPNMImage<'a> {
+    fn from(image: PNMImage<'a>) -> Self {
+        image.into_cow()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn copy_on_write() {
+        let raw_img = include_bytes!("./binary.ppm");
+        let ppm_img = PNMImage::from_parse(raw_img).unwrap();
+        let mut cow = ppm_img.clone().into_cow();
+        assert!(cow.is_borrowed());
+        assert_eq!(cow.as_image(), ppm_img);
+        assert_eq!(cow.header().width, 64);
+
+        cow.pixel_data_mut()[..3].copy_from_slice(&[1, 2, 3]);
+        assert!(!cow.is_borrowed());
+        assert_eq!(cow.as_image().pixel_rgb(0, 0), Some((1, 2, 3)));
+        assert_eq!(ppm_img.pixel_rgb(0, 0), Some((0, 0, 0)));
+    }
+}
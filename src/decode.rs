@@ -0,0 +1,300 @@
+use crate::parallel::for_each_row;
+use crate::{BufferTooSmall, PNMImage, Sample};
+
+/// The output layouts [`PNMImage::decode_into`] can write
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PixelFormat {
+    /// Three bytes per pixel in red, green, blue order
+    Rgb888,
+    /// Three bytes per pixel in blue, green, red order
+    Bgr888,
+    /// Two bytes per pixel holding 5 bits of red, 6 bits of green and 5 bits of blue, little endian
+    Rgb565Le,
+    /// Two bytes per pixel holding 5 bits of red, 6 bits of green and 5 bits of blue, big endian
+    Rgb565Be,
+    /// Four bytes per pixel in red, green, blue, alpha order. Alpha is taken from images whose
+    /// tuple type ends in `_ALPHA` like for [`PNMImage::pixel_rgba`], other images are opaque.
+    Rgba8888,
+    /// One byte per pixel holding its luma
+    Luma8,
+    /// One bit per pixel, most significant bit first, with every row padded to a whole byte.
+    /// A bit is set for pixels brighter than half the maximum pixel value.
+    Mono1,
+}
+
+impl PixelFormat {
+    /// Returns the number of bytes a row of `width` pixels takes up in this format.
+    pub fn row_len(self, width: usize) -> usize {
+        match self {
+            PixelFormat::Rgb888 | PixelFormat::Bgr888 => width.saturating_mul(3),
+            PixelFormat::Rgb565Le | PixelFormat::Rgb565Be => width.saturating_mul(2),
+            PixelFormat::Rgba8888 => width.saturating_mul(4),
+            PixelFormat::Luma8 => width,
+            PixelFormat::Mono1 => width.div_ceil(8),
+        }
+    }
+
+    /// Returns the number of bytes an image of `width` x `height` pixels takes up in this format.
+    pub fn buffer_len(self, width: usize, height: usize) -> usize {
+        self.row_len(width).saturating_mul(height)
+    }
+}
+
+impl PNMImage<'_> {
+    /// Decodes all pixels into `out` in the given pixel format, in row-major order
+    ///
+    /// Samples are scaled from `0..=maximum_pixel` to 8 bits, so bitmaps decode to black and white
+    /// and images of any maximum pixel value cover the full range. Pixels missing from the pixel
+    /// data are left untouched in `out`.
+    ///
+    /// # Arguments
+    ///
+    /// * `format` - The layout of the pixels written to `out`
+    /// * `out` - A buffer of at least [`PixelFormat::buffer_len`] bytes receiving the pixels
+    pub fn decode_into(&self, format: PixelFormat, out: &mut [u8]) -> Result<(), BufferTooSmall> {
+        self.decode_into_strided(format, out, format.row_len(self.width()))
+    }
+
+    /// Decodes all pixels into `out` like [`decode_into`](Self::decode_into), starting every row
+    /// `row_stride` bytes after the previous one
+    ///
+    /// This allows rows to be padded as required for texture uploads, e.g. to a multiple of 4 bytes
+    /// with `format.row_len(width).next_multiple_of(4)`. The padding bytes are left untouched.
+    ///
+    /// # Arguments
+    ///
+    /// * `format` - The layout of the pixels written to `out`
+    /// * `out` - A buffer receiving the pixels, which needs to hold `height - 1` padded rows and one more row
+    /// * `row_stride` - The distance between the starts of two rows in bytes
+    ///
+    /// # Panics
+    ///
+    /// Panics if `row_stride` is smaller than [`PixelFormat::row_len`] of the image width.
+    pub fn decode_into_strided(
+        &self,
+        format: PixelFormat,
+        out: &mut [u8],
+        row_stride: usize,
+    ) -> Result<(), BufferTooSmall> {
+        self.decode_into_with(format, out, row_stride, false)
+    }
+
+    /// Decodes all pixels into `out` with rows `row_stride` bytes apart, in parallel if `parallel` is set.
+    pub(crate) fn decode_into_with(
+        &self,
+        format: PixelFormat,
+        out: &mut [u8],
+        row_stride: usize,
+        parallel: bool,
+    ) -> Result<(), BufferTooSmall> {
+        let (width, height) = (self.width(), self.height());
+        let row_len = format.row_len(width);
+        assert!(row_stride >= row_len, "row stride is smaller than a row");
+        let required = match height {
+            0 => 0,
+            _ => row_stride
+                .saturating_mul(height - 1)
+                .saturating_add(row_len),
+        };
+        if out.len() < required {
+            return Err(BufferTooSmall { required });
+        }
+        if row_len == 0 {
+            return Ok(());
+        }
+        let src_row_len = self.row_len().unwrap_or_default();
+        let rgb565 =
+            |(r, g, b): (u8, u8, u8)| (r as u16 >> 3) << 11 | (g as u16 >> 2) << 5 | b as u16 >> 3;
+        let maximum_pixel = self.maximum_pixel().min(65535) as u16;
+        let to_u8 = |value: u16| u8::from_sample(value, maximum_pixel);
+        let has_alpha = self.tuple_type().ends_with("_ALPHA") && self.depth() > 1;
+        for_each_row(&mut out[..required], row_stride, parallel, |y, dst| {
+            let dst = &mut dst[..row_len];
+            let src = self.pixel_data().get(y * src_row_len..).unwrap_or(&[]);
+            let pixels = (0..width).map_while(|x| self.decode_at::<u8>(src, x));
+            match format {
+                PixelFormat::Rgb888 => {
+                    for (px, (r, g, b)) in dst.chunks_exact_mut(3).zip(pixels) {
+                        px.copy_from_slice(&[r, g, b]);
+                    }
+                }
+                PixelFormat::Bgr888 => {
+                    for (px, (r, g, b)) in dst.chunks_exact_mut(3).zip(pixels) {
+                        px.copy_from_slice(&[b, g, r]);
+                    }
+                }
+                PixelFormat::Rgb565Le => {
+                    for (px, rgb) in dst.chunks_exact_mut(2).zip(pixels) {
+                        px.copy_from_slice(&rgb565(rgb).to_le_bytes());
+                    }
+                }
+                PixelFormat::Rgb565Be => {
+                    for (px, rgb) in dst.chunks_exact_mut(2).zip(pixels) {
+                        px.copy_from_slice(&rgb565(rgb).to_be_bytes());
+                    }
+                }
+                PixelFormat::Rgba8888 => {
+                    let pixel_size = self.pixel_size();
+                    for (x, (px, (r, g, b))) in dst.chunks_exact_mut(4).zip(pixels).enumerate() {
+                        // the alpha sample is the last one of the pixel, which was decoded already
+                        let end = (x + 1) * pixel_size;
+                        let a = match (has_alpha, self.sample_size()) {
+                            (false, _) => 0xff,
+                            (true, 2) => to_u8(u16::from_be_bytes([src[end - 2], src[end - 1]])),
+                            (true, _) => to_u8(src[end - 1] as u16),
+                        };
+                        px.copy_from_slice(&[r, g, b, a]);
+                    }
+                }
+                PixelFormat::Luma8 => {
+                    let pixels = (0..width).map_while(|x| self.decode_at::<u16>(src, x));
+                    for (px, (r, g, b)) in dst.iter_mut().zip(pixels) {
+                        *px = to_u8(crate::gray::luma(r, g, b));
+                    }
+                }
+                PixelFormat::Mono1 => {
+                    let threshold = self.maximum_pixel() / 2;
+                    if width % 8 != 0 {
+                        // clear the bits padding the row to a whole byte
+                        dst[row_len - 1] &= !(0xff >> (width % 8));
+                    }
+                    let pixels = (0..width).map_while(|x| self.decode_at::<u16>(src, x));
+                    for (x, (r, g, b)) in pixels.enumerate() {
+                        let mask = 0x80 >> (x % 8);
+                        if crate::gray::luma(r, g, b) as usize > threshold {
+                            dst[x / 8] |= mask;
+                        } else {
+                            dst[x / 8] &= !mask;
+                        }
+                    }
+                }
+            }
+        });
+        Ok(())
+    }
+
+    /// Splits the pixels into separate red, green and blue planes in a single pass
+    ///
+    /// Every plane receives one byte per pixel in row-major order. Samples are scaled from
+    /// `0..=maximum_pixel` to `0..=255`, and the gray value of grayscale images is written to
+    /// all three planes.
+    ///
+    /// # Arguments
+    ///
+    /// * `r` - A buffer of at least `width * height` bytes receiving the red plane
+    /// * `g` - A buffer of at least `width * height` bytes receiving the green plane
+    /// * `b` - A buffer of at least `width * height` bytes receiving the blue plane
+    pub fn split_channels(
+        &self,
+        r: &mut [u8],
+        g: &mut [u8],
+        b: &mut [u8],
+    ) -> Result<(), BufferTooSmall> {
+        let required = self.width().saturating_mul(self.height());
+        if r.len().min(g.len()).min(b.len()) < required {
+            return Err(BufferTooSmall { required });
+        }
+        let planes = r.iter_mut().zip(g.iter_mut()).zip(b.iter_mut());
+        for (((r, g), b), px) in planes.zip(self.pixels::<u8>()) {
+            (*r, *g, *b) = px;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn decode_into() {
+        let raw_img = b"P6\n2 1\n255\n\xff\x00\x00\x00\x00\xff";
+        let ppm_img = PNMImage::from_parse(raw_img).unwrap();
+        let mut out = [0; 8];
+
+        ppm_img.decode_into(PixelFormat::Bgr888, &mut out).unwrap();
+        assert_eq!(out[..6], [0, 0, 0xff, 0xff, 0, 0]);
+        ppm_img
+            .decode_into(PixelFormat::Rgb565Le, &mut out)
+            .unwrap();
+        assert_eq!(out[..4], [0x00, 0xf8, 0x1f, 0x00]);
+        ppm_img
+            .decode_into(PixelFormat::Rgb565Be, &mut out)
+            .unwrap();
+        assert_eq!(out[..4], [0xf8, 0x00, 0x00, 0x1f]);
+        ppm_img
+            .decode_into(PixelFormat::Rgba8888, &mut out)
+            .unwrap();
+        assert_eq!(out, [0xff, 0, 0, 0xff, 0, 0, 0xff, 0xff]);
+        ppm_img.decode_into(PixelFormat::Luma8, &mut out).unwrap();
+        assert_eq!(out[..2], [77, 29]);
+
+        let pbm_img = PNMImage::from_parse(b"P4\n10 2\n\x40\x00\xff\xc0").unwrap();
+        pbm_img.decode_into(PixelFormat::Mono1, &mut out).unwrap();
+        assert_eq!(out[..4], [0xbf, 0xc0, 0x00, 0x00]);
+        assert_eq!(
+            pbm_img.decode_into(PixelFormat::Rgb888, &mut out),
+            Err(BufferTooSmall { required: 60 })
+        );
+        let pbm_img = PNMImage::from_parse(b"P4\n2 1\n\x80").unwrap();
+        pbm_img
+            .decode_into(PixelFormat::Rgba8888, &mut out)
+            .unwrap();
+        assert_eq!(out, [0, 0, 0, 0xff, 0xff, 0xff, 0xff, 0xff]);
+        pbm_img.decode_into(PixelFormat::Luma8, &mut out).unwrap();
+        assert_eq!(out[..2], [0, 0xff]);
+
+        let pgm_img = PNMImage::from_parse(b"P5\n2 1\n15\n\x0f\x07").unwrap();
+        pgm_img.decode_into(PixelFormat::Rgb888, &mut out).unwrap();
+        assert_eq!(out[..6], [0xff, 0xff, 0xff, 0x77, 0x77, 0x77]);
+        let pam_img = PNMImage::from_parse(
+            b"P7\nWIDTH 1\nHEIGHT 1\nDEPTH 2\nMAXVAL 1023\nTUPLTYPE GRAYSCALE_ALPHA\nENDHDR\n\x03\xff\x02\x00",
+        )
+        .unwrap();
+        pam_img
+            .decode_into(PixelFormat::Rgba8888, &mut out)
+            .unwrap();
+        assert_eq!(out[..4], [0xff, 0xff, 0xff, 0x80]);
+    }
+
+    #[test]
+    fn decode_into_strided() {
+        let raw_img = b"P6\n1 2\n255\n\x01\x02\x03\x04\x05\x06";
+        let ppm_img = PNMImage::from_parse(raw_img).unwrap();
+        let mut out = [0xaa; 7];
+
+        let stride = PixelFormat::Rgb888.row_len(1).next_multiple_of(4);
+        ppm_img
+            .decode_into_strided(PixelFormat::Rgb888, &mut out, stride)
+            .unwrap();
+        assert_eq!(out, [1, 2, 3, 0xaa, 4, 5, 6]);
+        assert_eq!(
+            ppm_img.decode_into_strided(PixelFormat::Rgba8888, &mut out, 4),
+            Err(BufferTooSmall { required: 8 })
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "row stride is smaller than a row")]
+    fn decode_into_short_stride() {
+        let ppm_img = PNMImage::from_parse(b"P6\n1 1\n255\n\x01\x02\x03").unwrap();
+        let _ = ppm_img.decode_into_strided(PixelFormat::Rgb888, &mut [0; 8], 2);
+    }
+
+    #[test]
+    fn split_channels() {
+        let raw_img = include_bytes!("./binary.ppm");
+        let ppm_img = PNMImage::from_parse(raw_img).unwrap();
+        let (mut r, mut g, mut b) = ([0; 64 * 64], [0; 64 * 64], [0; 64 * 64]);
+        ppm_img.split_channels(&mut r, &mut g, &mut b).unwrap();
+
+        let idx = 7 * 64 + 31;
+        assert_eq!((r[idx], g[idx], b[idx]), (255, 0, 0));
+        let idx = 56 * 64 + 56;
+        assert_eq!((r[idx], g[idx], b[idx]), (0, 0, 255));
+        assert_eq!(
+            ppm_img.split_channels(&mut r, &mut g, &mut b[..10]),
+            Err(BufferTooSmall { required: 64 * 64 })
+        );
+    }
+}
@@ -0,0 +1,441 @@
+use crate::{LumaWeights, PNMImage};
+
+/// The formats an image can be encoded to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Encoding {
+    /// Binary PPM (P6)
+    PPMBinary,
+    /// Plain (ASCII) PPM (P3)
+    PPMAscii,
+    /// Binary PGM (P5). Colors of RGB images are converted to luma using the Rec. 601 weights.
+    PGMBinary,
+}
+
+/// Error type that represents the different PNM encoding errors
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EncodeError {
+    /// The output buffer is too small to hold the encoded image
+    BufferTooSmall {
+        /// The number of bytes the output buffer needs to hold
+        required: usize,
+    },
+    /// The image holds less pixel data than its header describes
+    InsufficientData,
+    /// The comment contains a line that does not start with `#`, a comment line given to
+    /// [`PNMImage::encode_with_comments`] spans several lines, or a metadata key is empty or
+    /// contains `=` or whitespace
+    InvalidComment,
+}
+
+/// Maximum line length of plain formats, as recommended by the PNM specification
+const MAX_LINE_LEN: usize = 70;
+
+/// Writes into a byte buffer, counting the bytes that did not fit
+struct Writer<'a> {
+    out: &'a mut [u8],
+    len: usize,
+}
+
+impl Writer<'_> {
+    fn bytes(&mut self, bytes: &[u8]) {
+        if let Some(dst) = self.out.get_mut(self.len..self.len + bytes.len()) {
+            dst.copy_from_slice(bytes);
+        }
+        self.len += bytes.len();
+    }
+
+    /// Writes `value` in decimal and returns the number of digits written.
+    fn dec(&mut self, value: usize) -> usize {
+        let mut digits = [0; 20];
+        let mut start = digits.len();
+        let mut value = value;
+        loop {
+            start -= 1;
+            digits[start] = b'0' + (value % 10) as u8;
+            value /= 10;
+            if value == 0 {
+                break;
+            }
+        }
+        self.bytes(&digits[start..]);
+        digits.len() - start
+    }
+
+    fn finish(self) -> Result<usize, EncodeError> {
+        if self.len > self.out.len() {
+            Err(EncodeError::BufferTooSmall { required: self.len })
+        } else {
+            Ok(self.len)
+        }
+    }
+}
+
+/// Returns the number of decimal digits of `value`.
+fn dec_len(value: usize) -> usize {
+    value.checked_ilog10().unwrap_or(0) as usize + 1
+}
+
+impl PNMImage<'_> {
+    /// Encodes the image into `out`
+    ///
+    /// Grayscale images encoded to a PPM format have their gray value replicated to all three channels.
+    /// Only the pixel data described by the header is written, so trailing bytes are dropped.
+    /// If `out` is too small, the error reports the required size so the call can be retried.
+    ///
+    /// # Arguments
+    ///
+    /// * `encoding` - The format to encode to
+    /// * `out` - A buffer receiving the encoded image
+    ///
+    /// # Returns
+    ///
+    /// A Result object containing the number of bytes written if successful, otherwise an EncodeError
+    pub fn encode(&self, encoding: Encoding, out: &mut [u8]) -> Result<usize, EncodeError> {
+        self.check_comment()?;
+        self.encode_with(encoding, LumaWeights::Rec601, out, |w| {
+            self.write_comment(w)
+        })
+    }
+
+    /// Converts the image to gray and encodes it as a binary PGM (P5) image into `out` in a single pass
+    ///
+    /// The comment and maximum pixel value of the image are kept. Grayscale images are written
+    /// unchanged, while colors of RGB images are converted using `weights`.
+    ///
+    /// # Arguments
+    ///
+    /// * `weights` - The weights of the red, green and blue samples
+    /// * `out` - A buffer receiving the encoded image
+    ///
+    /// # Returns
+    ///
+    /// A Result object containing the number of bytes written if successful, otherwise an EncodeError
+    pub fn to_grayscale(&self, weights: LumaWeights, out: &mut [u8]) -> Result<usize, EncodeError> {
+        self.check_comment()?;
+        self.encode_with(Encoding::PGMBinary, weights, out, |w| self.write_comment(w))
+    }
+
+    /// Encodes the image into `out` like [`encode`](Self::encode), replacing its comment by `comments`
+    ///
+    /// Every entry is written as a comment line of its own, prefixed by `# `.
+    ///
+    /// # Arguments
+    ///
+    /// * `encoding` - The format to encode to
+    /// * `comments` - The comment lines, without the leading `#` and without line breaks
+    /// * `out` - A buffer receiving the encoded image
+    ///
+    /// # Returns
+    ///
+    /// A Result object containing the number of bytes written if successful, otherwise an EncodeError
+    pub fn encode_with_comments(
+        &self,
+        encoding: Encoding,
+        comments: &[&str],
+        out: &mut [u8],
+    ) -> Result<usize, EncodeError> {
+        if comments.iter().any(|line| line.contains(['\n', '\r'])) {
+            return Err(EncodeError::InvalidComment);
+        }
+        self.encode_with(encoding, LumaWeights::Rec601, out, |w| {
+            for line in comments {
+                w.bytes(b"# ");
+                w.bytes(line.as_bytes());
+                w.bytes(b"\n");
+            }
+        })
+    }
+
+    /// Encodes the image into `out` like [`encode`](Self::encode), appending `metadata` to its comment
+    ///
+    /// Every entry is written as a comment line of the form `# key=value`, which can be read back
+    /// with [`metadata`](Self::metadata).
+    ///
+    /// # Arguments
+    ///
+    /// * `encoding` - The format to encode to
+    /// * `metadata` - Pairs of keys and values, e.g. `("exposure", "12ms")`
+    /// * `out` - A buffer receiving the encoded image
+    ///
+    /// # Returns
+    ///
+    /// A Result object containing the number of bytes written if successful, otherwise an EncodeError
+    pub fn encode_with_metadata(
+        &self,
+        encoding: Encoding,
+        metadata: &[(&str, &str)],
+        out: &mut [u8],
+    ) -> Result<usize, EncodeError> {
+        self.check_comment()?;
+        let valid = metadata.iter().all(|(key, value)| {
+            !key.is_empty()
+                && !key.contains(|c: char| c == '=' || c.is_whitespace())
+                && !value.contains(['\n', '\r'])
+        });
+        if !valid {
+            return Err(EncodeError::InvalidComment);
+        }
+        self.encode_with(encoding, LumaWeights::Rec601, out, |w| {
+            self.write_comment(w);
+            for (key, value) in metadata {
+                w.bytes(b"# ");
+                w.bytes(key.as_bytes());
+                w.bytes(b"=");
+                w.bytes(value.as_bytes());
+                w.bytes(b"\n");
+            }
+        })
+    }
+
+    /// Encodes the image into `out` like [`encode`](Self::encode), padding the header so the pixel
+    /// data starts at a multiple of `align` bytes from the start of `out`
+    ///
+    /// The padding is written as leading zeros of the width, so no comment is added and the header
+    /// describes the same image. If `out` itself is aligned, e.g. for DMA transfers, the pixel data
+    /// of the encoded image is aligned as well. An `align` of 0 or 1 does not pad the header.
+    ///
+    /// # Arguments
+    ///
+    /// * `encoding` - The format to encode to
+    /// * `align` - The alignment of the pixel data in bytes
+    /// * `out` - A buffer receiving the encoded image
+    ///
+    /// # Returns
+    ///
+    /// A Result object containing the number of bytes written if successful, otherwise an EncodeError
+    pub fn encode_aligned(
+        &self,
+        encoding: Encoding,
+        align: usize,
+        out: &mut [u8],
+    ) -> Result<usize, EncodeError> {
+        self.check_comment()?;
+        // the header lines following the comment
+        let fields_len =
+            dec_len(self.width()) + dec_len(self.height()) + dec_len(self.maximum_pixel()) + 3;
+        self.encode_with(encoding, LumaWeights::Rec601, out, |w| {
+            self.write_comment(w);
+            if align <= 1 {
+                return;
+            }
+            // leading zeros of the width, which directly follows the comment, keep its value
+            let padding = (align - (w.len + fields_len) % align) % align;
+            for _ in 0..padding {
+                w.bytes(b"0");
+            }
+        })
+    }
+
+    /// Checks that every line of the comment of the image starts with `#`.
+    fn check_comment(&self) -> Result<(), EncodeError> {
+        let comment = self.comment();
+        if !comment.is_empty() && !comment.lines().all(|line| line.starts_with('#')) {
+            return Err(EncodeError::InvalidComment);
+        }
+        Ok(())
+    }
+
+    /// Writes the comment of the image, if any.
+    fn write_comment(&self, w: &mut Writer<'_>) {
+        let comment = self.comment();
+        if !comment.is_empty() {
+            w.bytes(comment.as_bytes());
+            w.bytes(b"\n");
+        }
+    }
+
+    /// Encodes the image into `out`, calling `write_comment` to write the comment lines of the header.
+    /// Colors encoded to PGM are converted using `weights`.
+    fn encode_with(
+        &self,
+        encoding: Encoding,
+        weights: LumaWeights,
+        out: &mut [u8],
+        write_comment: impl FnOnce(&mut Writer<'_>),
+    ) -> Result<usize, EncodeError> {
+        let raster = self
+            .raster_len()
+            .and_then(|len| self.pixel_data().get(..len))
+            .ok_or(EncodeError::InsufficientData)?;
+
+        let mut w = Writer { out, len: 0 };
+        w.bytes(match encoding {
+            Encoding::PPMBinary => b"P6\n",
+            Encoding::PPMAscii => b"P3\n",
+            Encoding::PGMBinary => b"P5\n",
+        });
+        write_comment(&mut w);
+        w.dec(self.width());
+        w.bytes(b" ");
+        w.dec(self.height());
+        w.bytes(b"\n");
+        w.dec(self.maximum_pixel());
+        w.bytes(b"\n");
+
+        let sample_size = self.sample_size();
+        let write_sample = |w: &mut Writer<'_>, value: u16| {
+            if sample_size == 2 {
+                w.bytes(&value.to_be_bytes());
+            } else {
+                w.bytes(&[value as u8]);
+            }
+        };
+        let pixels = self.pixels::<u16>();
+        match encoding {
+            Encoding::PPMBinary if self.channels() == 3 => w.bytes(raster),
+            Encoding::PGMBinary if self.channels() == 1 && !self.is_bitmap() => w.bytes(raster),
+            Encoding::PPMBinary => {
+                for (r, g, b) in pixels {
+                    for value in [r, g, b] {
+                        write_sample(&mut w, value);
+                    }
+                }
+            }
+            Encoding::PPMAscii => {
+                let mut line_len = 0;
+                for (r, g, b) in pixels {
+                    for value in [r, g, b] {
+                        // keep lines within the recommended length, a sample has at most 5 digits
+                        if line_len + 6 > MAX_LINE_LEN {
+                            w.bytes(b"\n");
+                            line_len = 0;
+                        } else if line_len > 0 {
+                            w.bytes(b" ");
+                            line_len += 1;
+                        }
+                        line_len += w.dec(value as usize);
+                    }
+                }
+                w.bytes(b"\n");
+            }
+            Encoding::PGMBinary => {
+                for (r, g, b) in pixels {
+                    write_sample(&mut w, weights.apply(r, g, b));
+                }
+            }
+        }
+        w.finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn encode_roundtrip() {
+        let raw_img = include_bytes!("./binary.ppm");
+        let ppm_img = PNMImage::from_parse(raw_img).unwrap();
+        let mut out = [0; 64 * 64 * 12 + 128];
+
+        let len = ppm_img.encode(Encoding::PPMBinary, &mut out).unwrap();
+        assert_eq!(&out[..len], &raw_img[..]);
+
+        let len = ppm_img.encode(Encoding::PPMAscii, &mut out).unwrap();
+        assert!(out[..len]
+            .split(|&b| b == b'\n')
+            .all(|line| line.len() <= 70));
+        let mut scratch = [0; 64 * 64 * 3];
+        let ascii_img = PNMImage::from_parse_ascii(&out[..len], &mut scratch).unwrap();
+        assert_eq!(ascii_img.comment(), ppm_img.comment());
+        assert_eq!(ascii_img.pixel_data(), ppm_img.pixel_data());
+
+        assert_eq!(
+            ppm_img.encode(Encoding::PPMBinary, &mut out[..100]),
+            Err(EncodeError::BufferTooSmall {
+                required: raw_img.len()
+            })
+        );
+    }
+
+    #[test]
+    fn encode_comments() {
+        let gray_img = PNMImage::from_parse(b"P5\n# scan\n1 1\n255\n\x10").unwrap();
+        let mut out = [0; 64];
+
+        let len = gray_img
+            .encode_with_comments(Encoding::PGMBinary, &["first", "second"], &mut out)
+            .unwrap();
+        assert_eq!(&out[..len], b"P5\n# first\n# second\n1 1\n255\n\x10");
+
+        let metadata = [("exposure", "12ms"), ("gain", "2.5")];
+        let len = gray_img
+            .encode_with_metadata(Encoding::PGMBinary, &metadata, &mut out)
+            .unwrap();
+        let encoded = PNMImage::from_parse(&out[..len]).unwrap();
+        assert_eq!(encoded.comment(), "# scan\n# exposure=12ms\n# gain=2.5");
+        assert!(encoded.metadata().eq(metadata));
+        assert_eq!(encoded.metadata_value("gain"), Some("2.5"));
+
+        assert_eq!(
+            gray_img.encode_with_comments(Encoding::PGMBinary, &["a\nb"], &mut out),
+            Err(EncodeError::InvalidComment)
+        );
+        assert_eq!(
+            gray_img.encode_with_metadata(Encoding::PGMBinary, &[("a b", "1")], &mut out),
+            Err(EncodeError::InvalidComment)
+        );
+    }
+
+    #[test]
+    fn to_grayscale() {
+        let ppm_img =
+            PNMImage::from_parse(b"P6\n# ocr\n2 1\n255\n\x00\xff\x00\x00\x00\xff").unwrap();
+        let mut out = [0; 32];
+
+        let len = ppm_img.to_grayscale(LumaWeights::Rec601, &mut out).unwrap();
+        assert_eq!(&out[..len], b"P5\n# ocr\n2 1\n255\n\x95\x1d");
+        let len = ppm_img.to_grayscale(LumaWeights::Rec709, &mut out).unwrap();
+        assert_eq!(out[len - 2..len], [182, 19]);
+        let weights = LumaWeights::Custom { r: 0, g: 1, b: 1 };
+        let len = ppm_img.to_grayscale(weights, &mut out).unwrap();
+        assert_eq!(out[len - 2..len], [128, 128]);
+        assert_eq!(
+            ppm_img.to_grayscale(LumaWeights::Average, &mut out[..4]),
+            Err(EncodeError::BufferTooSmall { required: 19 })
+        );
+    }
+
+    #[test]
+    fn encode_aligned() {
+        let ppm_img = PNMImage::from_parse(b"P6\n# dma\n1 1\n255\n\x01\x02\x03").unwrap();
+        let mut out = [0; 64];
+
+        for align in [0, 1, 2, 4, 16, 32] {
+            let len = ppm_img
+                .encode_aligned(Encoding::PPMBinary, align, &mut out)
+                .unwrap();
+            let offset = len - 3;
+            assert_eq!(offset % align.max(1), 0);
+            let encoded = PNMImage::from_parse(&out[..len]).unwrap();
+            assert_eq!(encoded, ppm_img);
+            assert!(encoded.comments().eq(["dma"]));
+        }
+        // 17 header bytes need a single byte of padding
+        let len = ppm_img
+            .encode_aligned(Encoding::PPMBinary, 2, &mut out)
+            .unwrap();
+        assert_eq!(&out[..len], b"P6\n# dma\n01 1\n255\n\x01\x02\x03");
+    }
+
+    #[test]
+    fn encode_gray() {
+        let ppm_img = PNMImage::from_parse(b"P6\n2 1\n255\n\xff\xff\xff\xff\x00\x00").unwrap();
+        let mut out = [0; 32];
+
+        let len = ppm_img.encode(Encoding::PGMBinary, &mut out).unwrap();
+        assert_eq!(&out[..len], b"P5\n2 1\n255\n\xff\x4d");
+
+        let gray_img = PNMImage::from_parse(b"P5\n2 1\n255\n\xff\x4d").unwrap();
+        let len = gray_img.encode(Encoding::PGMBinary, &mut out).unwrap();
+        assert_eq!(&out[..len], b"P5\n2 1\n255\n\xff\x4d");
+        let len = gray_img.encode(Encoding::PPMAscii, &mut out).unwrap();
+        assert_eq!(&out[..len], b"P3\n2 1\n255\n255 255 255 77 77 77\n");
+
+        let short = PNMImage::from_parse(b"P6\n2 1\n255\n\xff\xff\xff").unwrap();
+        assert_eq!(
+            short.encode(Encoding::PGMBinary, &mut out),
+            Err(EncodeError::InsufficientData)
+        );
+    }
+}
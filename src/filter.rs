@@ -0,0 +1,128 @@
+//! 3x3 convolution filters and kernel presets for [`PNMImage::convolve3x3`].
+//!
+//! Kernels are given in row-major order, with the weight of the pixel itself in the middle.
+
+use crate::parallel::for_each_row;
+use crate::{BufferTooSmall, PNMImage};
+
+/// Averages every pixel with its neighbors, use with a divisor of 9
+pub const BOX_BLUR: [i16; 9] = [1, 1, 1, 1, 1, 1, 1, 1, 1];
+
+/// Weighs pixels by their distance, a smoother blur than [`BOX_BLUR`], use with a divisor of 16
+pub const GAUSSIAN_BLUR: [i16; 9] = [1, 2, 1, 2, 4, 2, 1, 2, 1];
+
+/// Emphasizes differences to the direct neighbors, use with a divisor of 1
+pub const SHARPEN: [i16; 9] = [0, -1, 0, -1, 5, -1, 0, -1, 0];
+
+/// Detects vertical edges where the image gets brighter to the right, use with a divisor of 1
+pub const SOBEL_X: [i16; 9] = [-1, 0, 1, -2, 0, 2, -1, 0, 1];
+
+/// Detects horizontal edges where the image gets brighter to the bottom, use with a divisor of 1
+pub const SOBEL_Y: [i16; 9] = [-1, -2, -1, 0, 0, 0, 1, 2, 1];
+
+impl PNMImage<'_> {
+    /// Convolves the image with a 3x3 `kernel` and writes the result into `out`
+    ///
+    /// Every output sample is the weighted sum of the surrounding samples of the same channel
+    /// divided by `divisor`, clamped to `0..=255`. Pixels beyond the edges of the image repeat
+    /// the nearest edge pixel, and pixels missing from the pixel data are treated as black.
+    /// The result is written as 8-bit RGB triples in row-major order, with samples scaled from
+    /// `0..=maximum_pixel` to `0..=255`.
+    ///
+    /// # Arguments
+    ///
+    /// * `kernel` - The weights of the neighborhood in row-major order, see the presets in [`filter`](crate::filter)
+    /// * `divisor` - The divisor of the weighted sum, a divisor of 0 is treated as 1
+    /// * `out` - A buffer of at least `width * height * 3` bytes receiving the filtered image
+    pub fn convolve3x3(
+        &self,
+        kernel: &[i16; 9],
+        divisor: i16,
+        out: &mut [u8],
+    ) -> Result<(), BufferTooSmall> {
+        self.convolve3x3_with(kernel, divisor, out, false)
+    }
+
+    /// Convolves the image with `kernel` into `out`, in parallel if `parallel` is set.
+    pub(crate) fn convolve3x3_with(
+        &self,
+        kernel: &[i16; 9],
+        divisor: i16,
+        out: &mut [u8],
+        parallel: bool,
+    ) -> Result<(), BufferTooSmall> {
+        let (width, height) = (self.width(), self.height());
+        let required = width.saturating_mul(height).saturating_mul(3);
+        if out.len() < required {
+            return Err(BufferTooSmall { required });
+        }
+        let row_len = self.row_len().unwrap_or_default();
+        if required == 0 {
+            return Ok(());
+        }
+        let divisor = if divisor == 0 { 1 } else { divisor as i32 };
+        let pixel_data = self.pixel_data();
+        let row = |y: usize| {
+            pixel_data
+                .get(y * row_len..(y + 1) * row_len)
+                .unwrap_or(&[])
+        };
+
+        for_each_row(&mut out[..required], width * 3, parallel, |y, dst| {
+            // the rows above and below, repeating the edge rows
+            let rows = [
+                row(y.saturating_sub(1)),
+                row(y),
+                row((y + 1).min(height - 1)),
+            ];
+            for (x, px) in dst.chunks_exact_mut(3).enumerate() {
+                let mut sum = [0i32; 3];
+                for (i, &weight) in kernel.iter().enumerate() {
+                    let sx = (x + i % 3).saturating_sub(1).min(width - 1);
+                    let (r, g, b) = self.decode_at::<u8>(rows[i / 3], sx).unwrap_or((0, 0, 0));
+                    for (sum, value) in sum.iter_mut().zip([r, g, b]) {
+                        *sum += value as i32 * weight as i32;
+                    }
+                }
+                for (dst, sum) in px.iter_mut().zip(sum) {
+                    *dst = (sum / divisor).clamp(0, 255) as u8;
+                }
+            }
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn convolve3x3() {
+        let gray_img = PNMImage::from_parse(b"P5\n4 1\n255\n\x00\x00\xff\xff").unwrap();
+        let mut out = [0; 12];
+        let red = |out: &[u8; 12]| [out[0], out[3], out[6], out[9]];
+
+        gray_img.convolve3x3(&BOX_BLUR, 9, &mut out).unwrap();
+        assert_eq!(red(&out), [0, 85, 170, 255]);
+        gray_img.convolve3x3(&SOBEL_X, 1, &mut out).unwrap();
+        assert_eq!(red(&out), [0, 255, 255, 0]);
+        gray_img.convolve3x3(&SOBEL_Y, 1, &mut out).unwrap();
+        assert_eq!(red(&out), [0, 0, 0, 0]);
+        gray_img.convolve3x3(&SHARPEN, 0, &mut out).unwrap();
+        assert_eq!(red(&out), [0, 0, 255, 255]);
+
+        let raw_img = include_bytes!("./binary.ppm");
+        let ppm_img = PNMImage::from_parse(raw_img).unwrap();
+        let mut out = [0; 64 * 64 * 3];
+        ppm_img.convolve3x3(&GAUSSIAN_BLUR, 16, &mut out).unwrap();
+        let idx = (56 * 64 + 56) * 3;
+        assert_eq!(out[idx..idx + 3], [0, 0, 255]);
+        assert_eq!(
+            ppm_img.convolve3x3(&BOX_BLUR, 9, &mut out[..10]),
+            Err(BufferTooSmall {
+                required: 64 * 64 * 3
+            })
+        );
+    }
+}
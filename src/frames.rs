@@ -0,0 +1,112 @@
+use crate::{PNMError, PNMImage};
+
+/// A single image of a multi-image stream
+#[derive(Clone, Debug)]
+pub struct Frame<'a> {
+    /// The image of this frame
+    pub image: PNMImage<'a>,
+    /// The byte offset of this frame within the stream
+    pub offset: usize,
+    /// How long this frame should be shown, in milliseconds.
+    ///
+    /// Taken from the `delay` entry of the frame's [`metadata`](PNMImage::metadata), e.g. `# delay=40`.
+    pub delay_ms: Option<u32>,
+}
+
+/// An iterator over the frames of concatenated PNM images, created by [`PNMImage::frames`]
+#[derive(Clone, Debug)]
+pub struct Frames<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+    done: bool,
+}
+
+impl<'a> PNMImage<'a> {
+    /// Returns an iterator over the images of a stream of concatenated PNM images,
+    /// such as the frames of a simple animation.
+    ///
+    /// Whitespace between frames is skipped. The iterator stops after the first error.
+    pub fn frames(bytes: &'a [u8]) -> Frames<'a> {
+        Frames {
+            bytes,
+            offset: 0,
+            done: false,
+        }
+    }
+}
+
+impl<'a> Iterator for Frames<'a> {
+    type Item = Result<Frame<'a>, PNMError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        while self
+            .bytes
+            .get(self.offset)
+            .is_some_and(u8::is_ascii_whitespace)
+        {
+            self.offset += 1;
+        }
+        if self.offset >= self.bytes.len() {
+            self.done = true;
+            return None;
+        }
+
+        match PNMImage::from_parse_prefix(&self.bytes[self.offset..]) {
+            Ok((image, rest)) => {
+                let offset = self.offset;
+                self.offset = self.bytes.len() - rest.len();
+                let delay_ms = image.metadata_value("delay").and_then(|ms| ms.parse().ok());
+                Some(Ok(Frame {
+                    image,
+                    offset,
+                    delay_ms,
+                }))
+            }
+            Err(err) => {
+                self.done = true;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn frames() {
+        let stream = b"P6\n# delay=40\n1 1\n255\n\x01\x02\x03P6\n# delay = 80\n1 2\n255\n\x04\x05\x06\x07\x08\x09P6\n1 1\n255\n\x00\x00\x00\n";
+        let mut frames = PNMImage::frames(stream);
+
+        let first = frames.next().unwrap().unwrap();
+        assert_eq!(first.offset, 0);
+        assert_eq!(first.delay_ms, Some(40));
+        assert_eq!(first.image.pixel_rgb(0, 0), Some((1, 2, 3)));
+        assert_eq!(first.image.pixel_rgb(0, 1), None);
+
+        let second = frames.next().unwrap().unwrap();
+        assert_eq!(second.offset, 25);
+        assert_eq!(second.delay_ms, Some(80));
+        assert_eq!(second.image.height(), 2);
+        assert_eq!(second.image.pixel_rgb(0, 1), Some((7, 8, 9)));
+
+        let third = frames.next().unwrap().unwrap();
+        assert_eq!(third.delay_ms, None);
+
+        assert!(frames.next().is_none());
+    }
+
+    #[test]
+    fn truncated_frame() {
+        let stream = b"P6\n1 1\n255\n\x01\x02\x03P6\n1 1\n255\n\x04";
+        let mut frames = PNMImage::frames(stream);
+
+        assert!(frames.next().unwrap().is_ok());
+        assert!(matches!(frames.next(), Some(Err(PNMError::UnexpectedEOF))));
+        assert!(frames.next().is_none());
+    }
+}
@@ -0,0 +1,175 @@
+//! Helpers for fuzzing the parser with structurally valid images.
+//!
+//! Fuzz targets can draw a [`PNMImage`] or [`PNMHeader`] from [`arbitrary::Unstructured`] data and pass it to
+//! [`roundtrip`], which yields encoded bytes to feed into the parser and checks that
+//! encoding and parsing agree with each other.
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+use crate::{Encoding, PNMFormat, PNMHeader, PNMImage};
+
+/// Upper bound on generated widths and heights, to keep inputs small
+const MAX_DIMENSION: usize = 256;
+
+/// The depths and tuple types of generated PAM images
+const PAM_TUPLE_TYPES: [(usize, &str); 4] = [
+    (1, "GRAYSCALE"),
+    (2, "GRAYSCALE_ALPHA"),
+    (3, "RGB"),
+    (4, "RGB_ALPHA"),
+];
+
+impl<'a> Arbitrary<'a> for PNMHeader<'a> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let format = *u.choose(&[
+            PNMFormat::PPM,
+            PNMFormat::PGM,
+            PNMFormat::PBM,
+            PNMFormat::PAM,
+        ])?;
+        let (depth, tuple_type) = match format {
+            PNMFormat::PPM => (3, "RGB"),
+            PNMFormat::PGM => (1, "GRAYSCALE"),
+            PNMFormat::PBM => (1, "BLACKANDWHITE"),
+            PNMFormat::PAM => *u.choose(&PAM_TUPLE_TYPES)?,
+        };
+        let maximum_pixel = match format {
+            PNMFormat::PBM => 1,
+            _ => u.int_in_range(1..=65535)?,
+        };
+        let comment = <&str>::arbitrary(u)?;
+        // only the first line of a comment starting with `#` is a valid comment
+        let comment = match comment.split('\n').next() {
+            Some(line) if line.starts_with('#') => line,
+            _ => "",
+        };
+        Ok(PNMHeader {
+            format,
+            width: u.int_in_range(0..=MAX_DIMENSION)?,
+            height: u.int_in_range(0..=MAX_DIMENSION)?,
+            depth,
+            maximum_pixel,
+            tuple_type,
+            comment,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for PNMImage<'a> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let header = PNMHeader::arbitrary(u)?;
+        let row_len = PNMImage::from_raw_parts_unchecked(header, &[])
+            .row_len()
+            .unwrap_or_default();
+        // shrink the image to the remaining data rather than failing
+        let height = match row_len {
+            0 => header.height,
+            row_len => header.height.min(u.len() / row_len),
+        };
+        let pixel_data = u.bytes(row_len * height)?;
+        Ok(PNMImage::from_raw_parts_unchecked(
+            PNMHeader { height, ..header },
+            pixel_data,
+        ))
+    }
+}
+
+/// Encodes `image` and parses the result again, returning the encoded image.
+///
+/// PPM and PGM images are encoded in their own format. As there are no PBM and PAM encodings,
+/// bitmaps are encoded as PGM and PAM images as PGM or PPM depending on their depth, which drops
+/// their alpha samples, and only their pixels are compared. If the image is encoded as PPM and all
+/// samples are within the maximum pixel value, the plain PPM encoding is checked as well.
+///
+/// # Panics
+///
+/// Panics if the image cannot be encoded or the parsed image differs from `image`.
+pub fn roundtrip(image: &PNMImage<'_>) -> Vec<u8> {
+    let encoding = match image.header().format {
+        PNMFormat::PPM => Encoding::PPMBinary,
+        PNMFormat::PGM | PNMFormat::PBM => Encoding::PGMBinary,
+        PNMFormat::PAM if image.channels() < 3 => Encoding::PGMBinary,
+        PNMFormat::PAM => Encoding::PPMBinary,
+    };
+    let binary = encode(image, encoding);
+    let parsed = PNMImage::from_parse(&binary).expect("failed to parse encoded image");
+    assert_same(image, &parsed);
+
+    let samples_valid = image
+        .pixel_data()
+        .chunks_exact(image.sample_size())
+        .all(|s| crate::sample_value(s) as usize <= image.maximum_pixel());
+    if encoding == Encoding::PPMBinary && samples_valid {
+        let ascii = encode(image, Encoding::PPMAscii);
+        let mut scratch = vec![0; parsed.pixel_data().len()];
+        let parsed = PNMImage::from_parse_ascii(&ascii, &mut scratch)
+            .expect("failed to parse encoded plain image");
+        assert_same(image, &parsed);
+    }
+
+    binary
+}
+
+fn encode(image: &PNMImage<'_>, encoding: Encoding) -> Vec<u8> {
+    let mut out = Vec::new();
+    if let Err(crate::EncodeError::BufferTooSmall { required }) = image.encode(encoding, &mut out) {
+        out.resize(required, 0);
+    }
+    let len = image
+        .encode(encoding, &mut out)
+        .expect("failed to encode image");
+    out.truncate(len);
+    out
+}
+
+fn assert_same(expected: &PNMImage<'_>, actual: &PNMImage<'_>) {
+    let (expected_header, actual_header) = (expected.header(), actual.header());
+    if expected_header.format == actual_header.format {
+        assert_eq!(expected_header, actual_header, "header differs");
+        assert_eq!(
+            expected.pixel_data(),
+            actual.pixel_data(),
+            "pixel data differs"
+        );
+    } else {
+        assert_eq!(
+            PNMHeader {
+                format: actual_header.format,
+                depth: actual_header.depth,
+                tuple_type: actual_header.tuple_type,
+                ..expected_header
+            },
+            actual_header,
+            "header differs"
+        );
+        assert!(
+            expected.pixels::<u16>().eq(actual.pixels::<u16>()),
+            "pixels differ"
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn arbitrary_roundtrip() {
+        let mut seed = [0u8; 4096];
+        let mut formats = Vec::new();
+        for round in 0..64u32 {
+            // cheap deterministic noise, good enough to exercise many shapes
+            for (i, b) in seed.iter_mut().enumerate() {
+                *b = ((i as u32 ^ round).wrapping_mul(2654435761) >> 13) as u8;
+            }
+            let mut u = Unstructured::new(&seed);
+            let image = PNMImage::arbitrary(&mut u).unwrap();
+            let bytes = roundtrip(&image);
+            assert!(bytes.starts_with(b"P6\n") || bytes.starts_with(b"P5\n"));
+            if !formats.contains(&image.header().format) {
+                formats.push(image.header().format);
+            }
+        }
+        assert_eq!(formats.len(), 4);
+    }
+}
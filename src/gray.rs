@@ -0,0 +1,111 @@
+use crate::{PNMImage, Sample};
+
+/// The weights of the red, green and blue samples when converting colors to gray
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LumaWeights {
+    /// The Rec. 601 weights used for standard definition video and JPEG
+    #[default]
+    Rec601,
+    /// The Rec. 709 weights used for HD video and sRGB
+    Rec709,
+    /// The plain average of all three samples
+    Average,
+    /// Custom weights, which are normalized by their sum
+    Custom {
+        /// The weight of the red sample
+        r: u16,
+        /// The weight of the green sample
+        g: u16,
+        /// The weight of the blue sample
+        b: u16,
+    },
+}
+
+impl LumaWeights {
+    /// Converts RGB samples to gray using these weights.
+    pub fn apply(self, r: u16, g: u16, b: u16) -> u16 {
+        let (wr, wg, wb) = match self {
+            LumaWeights::Rec601 => (77, 150, 29),
+            LumaWeights::Rec709 => (54, 183, 19),
+            LumaWeights::Average => (85, 86, 85),
+            LumaWeights::Custom { r, g, b } => (r as u32, g as u32, b as u32),
+        };
+        let sum = wr + wg + wb;
+        if sum == 0 {
+            return 0;
+        }
+        let (r, g, b) = (r as u64, g as u64, b as u64);
+        let weighted = r * wr as u64 + g * wg as u64 + b * wb as u64;
+        ((weighted + sum as u64 / 2) / sum as u64) as u16
+    }
+}
+
+/// Converts RGB samples to luma using the Rec. 601 weights.
+pub(crate) fn luma(r: u16, g: u16, b: u16) -> u16 {
+    LumaWeights::Rec601.apply(r, g, b)
+}
+
+impl PNMImage<'_> {
+    /// Returns the gray value of the pixel at the specified (x, y) coordinate.
+    /// Returns `None` if the pixel is outside the bounds of the image.
+    ///
+    /// Colors of RGB images are converted to luma using the Rec. 601 weights.
+    /// The gray value is scaled from `0..=maximum_pixel` to `0..=255`, so white pixels of bitmaps
+    /// read as 255. Use [`pixel_gray16`](Self::pixel_gray16) to read the raw sample.
+    pub fn pixel_gray(&self, x: usize, y: usize) -> Option<u8> {
+        self.gray(x, y)
+    }
+
+    /// Returns the gray value of the pixel at the specified (x, y) coordinate as a 16-bit sample.
+    /// Returns `None` if the pixel is outside the bounds of the image.
+    ///
+    /// Samples of images with a maximum pixel value above 255 are decoded as big-endian values,
+    /// samples of other images are widened without scaling.
+    pub fn pixel_gray16(&self, x: usize, y: usize) -> Option<u16> {
+        self.gray(x, y)
+    }
+
+    fn gray<S: Sample>(&self, x: usize, y: usize) -> Option<S> {
+        let (r, g, b) = self.pixel::<u16>(x, y)?;
+        let gray = if self.channels() < 3 {
+            r
+        } else {
+            luma(r, g, b)
+        };
+        Some(S::from_sample(gray, self.maximum_pixel().min(65535) as u16))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn gray_pixels() {
+        let pgm_img = PNMImage::from_parse(b"P5\n# gray\n2 2\n255\n\x00\x40\x80\xff").unwrap();
+        assert_eq!(pgm_img.comment(), "# gray");
+        assert_eq!(pgm_img.pixel_gray(1, 0), Some(0x40));
+        assert_eq!(pgm_img.pixel_gray16(1, 1), Some(0xff));
+        assert_eq!(pgm_img.pixel_rgb(0, 1), Some((0x80, 0x80, 0x80)));
+        assert_eq!(pgm_img.pixel_gray(2, 0), None);
+
+        let wide = PNMImage::from_parse(b"P5\n1 1\n65535\n\x12\x34").unwrap();
+        assert_eq!(wide.pixel_gray16(0, 0), Some(0x1234));
+        assert_eq!(wide.pixel_gray(0, 0), Some(0x12));
+        assert_eq!(wide.pixel_rgb16(0, 0), Some((0x1234, 0x1234, 0x1234)));
+
+        let ppm_img = PNMImage::from_parse(include_bytes!("./binary.ppm")).unwrap();
+        assert_eq!(ppm_img.pixel_gray(31, 31), Some(255));
+        assert_eq!(ppm_img.pixel_gray(31, 7), Some(77));
+    }
+
+    #[test]
+    fn plain_gray() {
+        let mut scratch = [0; 3];
+        let pgm_img = PNMImage::from_parse_ascii(b"P2\n3 1\n15\n0 7 15\n", &mut scratch).unwrap();
+        assert!(matches!(pgm_img, PNMImage::PGMBinary { .. }));
+        assert_eq!(pgm_img.pixel_gray(2, 0), Some(255));
+        assert_eq!(pgm_img.pixel_gray16(2, 0), Some(15));
+        assert_eq!(pgm_img.pixel_rgb(1, 0), Some((119, 119, 119)));
+    }
+}
@@ -0,0 +1,254 @@
+use crate::{PNMError, PNMHeader, PNMImage};
+
+/// A PNM image whose pixel data can be modified in place, e.g. before handing it to DMA
+#[derive(Debug)]
+pub struct PNMImageMut<'a> {
+    /// The image holding the header, its pixel data is empty
+    image: PNMImage<'a>,
+    pixel_data: &'a mut [u8],
+}
+
+impl<'a> PNMImageMut<'a> {
+    /// Parses a binary PNM image (P7, P6, P5 or P4) from a mutable byte array
+    ///
+    /// The pixel data is cut to exactly the size described by the header.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - A byte array containing the PNM image data
+    ///
+    /// # Returns
+    ///
+    /// A Result object containing the parsed PNMImageMut if successful, otherwise a PNMError
+    pub fn from_parse(bytes: &'a mut [u8]) -> Result<Self, PNMError> {
+        let (_, offset) = PNMImage::parse(bytes)?;
+        let (header, pixel_data) = bytes.split_at_mut(offset);
+        let header: &'a [u8] = header;
+        let (image, _) = PNMImage::parse(header)?;
+        let len = image
+            .raster_len()
+            .filter(|&len| len <= pixel_data.len())
+            .ok_or(PNMError::UnexpectedEOF)?;
+        Ok(Self {
+            image,
+            pixel_data: &mut pixel_data[..len],
+        })
+    }
+
+    /// Returns the header of the image.
+    pub fn header(&self) -> PNMHeader<'a> {
+        self.image.header()
+    }
+
+    /// Returns a read-only view of the image, giving access to all accessors of [`PNMImage`].
+    pub fn as_image(&self) -> PNMImage<'_> {
+        let mut image = self.image.clone();
+        image.set_pixel_data(self.pixel_data);
+        image
+    }
+
+    /// Returns the raw pixel data of the image.
+    pub fn pixel_data_mut(&mut self) -> &mut [u8] {
+        self.pixel_data
+    }
+
+    /// Mirrors the image along its vertical axis, so the left column becomes the right one.
+    pub fn flip_horizontal_in_place(&mut self) {
+        let width = self.image.width();
+        let row_len = self.image.row_len().unwrap_or_default();
+        if row_len == 0 {
+            return;
+        }
+        let bitmap = self.image.is_bitmap();
+        let pixel_size = self.image.pixel_size();
+        for row in self.pixel_data.chunks_exact_mut(row_len) {
+            for x in 0..width / 2 {
+                let mirrored = width - 1 - x;
+                if bitmap {
+                    let (a, b) = (bit(row, x), bit(row, mirrored));
+                    set_bit(row, x, b);
+                    set_bit(row, mirrored, a);
+                } else {
+                    for i in 0..pixel_size {
+                        row.swap(x * pixel_size + i, mirrored * pixel_size + i);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Mirrors the image along its horizontal axis, so the top row becomes the bottom one.
+    pub fn flip_vertical_in_place(&mut self) {
+        let row_len = self.image.row_len().unwrap_or_default();
+        let height = self.image.height();
+        if row_len == 0 {
+            return;
+        }
+        for y in 0..height / 2 {
+            let (top, bottom) = self.pixel_data.split_at_mut((height - 1 - y) * row_len);
+            top[y * row_len..(y + 1) * row_len].swap_with_slice(&mut bottom[..row_len]);
+        }
+    }
+
+    /// Rotates the image by 180 degrees.
+    pub fn rotate_180_in_place(&mut self) {
+        self.flip_vertical_in_place();
+        self.flip_horizontal_in_place();
+    }
+
+    /// Adds `delta` to every color sample, saturating at 0 and the maximum pixel value.
+    ///
+    /// `delta` is given relative to a maximum pixel value of 255 and scaled to that of the image,
+    /// so the same adjustment can be applied to images of any sample depth.
+    /// Alpha samples and bitmaps are left unchanged.
+    pub fn adjust_brightness(&mut self, delta: i16) {
+        let maximum_pixel = self.image.maximum_pixel() as i32;
+        let delta = delta as i32 * maximum_pixel / 255;
+        self.map_samples(|value| (value as i32 + delta).clamp(0, maximum_pixel) as u32);
+    }
+
+    /// Scales the distance of every color sample from the middle of the sample range by `factor`,
+    /// saturating at 0 and the maximum pixel value.
+    ///
+    /// `factor` is an 8.8 fixed-point number, so 256 leaves the image unchanged, 512 doubles the
+    /// contrast and 128 halves it. Alpha samples and bitmaps are left unchanged.
+    pub fn adjust_contrast(&mut self, factor: u16) {
+        let maximum_pixel = self.image.maximum_pixel() as i64;
+        let middle = (maximum_pixel + 1) / 2;
+        self.map_samples(|value| {
+            let scaled = ((value as i64 - middle) * factor as i64 + 128) >> 8;
+            (middle + scaled).clamp(0, maximum_pixel) as u32
+        });
+    }
+
+    /// Inverts every color sample, so black becomes white. Alpha samples are left unchanged.
+    ///
+    /// The bits padding the rows of bitmaps to a whole byte are cleared.
+    pub fn invert(&mut self) {
+        if self.image.is_bitmap() {
+            let width = self.image.width();
+            let row_len = width.div_ceil(8);
+            if row_len == 0 {
+                return;
+            }
+            let padding = row_len * 8 - width;
+            for row in self.pixel_data.chunks_exact_mut(row_len) {
+                row.iter_mut().for_each(|byte| *byte = !*byte);
+                row[row_len - 1] &= 0xff << padding;
+            }
+            return;
+        }
+        let maximum_pixel = self.image.maximum_pixel() as u32;
+        self.map_samples(|value| maximum_pixel.saturating_sub(value));
+    }
+
+    /// Replaces every color sample by the result of `f`. Bitmaps are left unchanged.
+    fn map_samples(&mut self, f: impl Fn(u32) -> u32) {
+        if self.image.is_bitmap() {
+            return;
+        }
+        let sample_size = self.image.sample_size();
+        let channels = self.image.channels();
+        let alpha = channels > 1 && self.image.tuple_type().ends_with("_ALPHA");
+        let color_len = if alpha { channels - 1 } else { channels } * sample_size;
+        for px in self.pixel_data.chunks_exact_mut(channels * sample_size) {
+            for sample in px[..color_len].chunks_exact_mut(sample_size) {
+                if let [high, low] = sample {
+                    let value = f(u16::from_be_bytes([*high, *low]) as u32) as u16;
+                    [*high, *low] = value.to_be_bytes();
+                } else {
+                    sample[0] = f(sample[0] as u32) as u8;
+                }
+            }
+        }
+    }
+}
+
+/// Returns bit `x` of a row of bitmap pixel data.
+fn bit(row: &[u8], x: usize) -> bool {
+    row[x / 8] & (0x80 >> (x % 8)) != 0
+}
+
+/// Sets bit `x` of a row of bitmap pixel data to `value`.
+fn set_bit(row: &mut [u8], x: usize, value: bool) {
+    if value {
+        row[x / 8] |= 0x80 >> (x % 8);
+    } else {
+        row[x / 8] &= !(0x80 >> (x % 8));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn flip_and_rotate() {
+        let mut raw_img = *b"P6\n3 2\n255\n\x01\x01\x01\x02\x02\x02\x03\x03\x03\x04\x04\x04\x05\x05\x05\x06\x06\x06";
+        let mut image = PNMImageMut::from_parse(&mut raw_img).unwrap();
+
+        image.flip_horizontal_in_place();
+        assert_eq!(image.as_image().pixel_rgb(0, 0), Some((3, 3, 3)));
+        assert_eq!(image.as_image().pixel_rgb(1, 1), Some((5, 5, 5)));
+
+        image.flip_vertical_in_place();
+        assert_eq!(image.as_image().pixel_rgb(0, 0), Some((6, 6, 6)));
+
+        image.rotate_180_in_place();
+        assert_eq!(
+            image.pixel_data_mut(),
+            b"\x01\x01\x01\x02\x02\x02\x03\x03\x03\x04\x04\x04\x05\x05\x05\x06\x06\x06"
+        );
+    }
+
+    #[test]
+    fn adjustments() {
+        let mut raw_img = *b"P6\n2 1\n255\n\x00\x80\xf0\x40\x60\xff";
+        let mut image = PNMImageMut::from_parse(&mut raw_img).unwrap();
+
+        image.adjust_brightness(32);
+        assert_eq!(image.pixel_data_mut(), [0x20, 0xa0, 0xff, 0x60, 0x80, 0xff]);
+        image.adjust_brightness(-64);
+        assert_eq!(image.pixel_data_mut(), [0x00, 0x60, 0xbf, 0x20, 0x40, 0xbf]);
+        image.adjust_contrast(512);
+        assert_eq!(image.pixel_data_mut(), [0x00, 0x40, 0xfe, 0x00, 0x00, 0xfe]);
+        image.invert();
+        assert_eq!(image.pixel_data_mut(), [0xff, 0xbf, 0x01, 0xff, 0xff, 0x01]);
+
+        let mut raw_img = *b"P7\nWIDTH 1\nHEIGHT 1\nDEPTH 2\nMAXVAL 1000\nTUPLTYPE GRAYSCALE_ALPHA\nENDHDR\n\x00\x64\x03\xe8";
+        let mut image = PNMImageMut::from_parse(&mut raw_img).unwrap();
+        image.adjust_brightness(255);
+        assert_eq!(
+            image.as_image().pixel_samples(0, 0),
+            Some(&[0x03, 0xe8, 0x03, 0xe8][..])
+        );
+        image.invert();
+        assert_eq!(
+            image.as_image().pixel_samples(0, 0),
+            Some(&[0, 0, 0x03, 0xe8][..])
+        );
+
+        let mut raw_img = *b"P4\n8 1\n\x0f";
+        let mut image = PNMImageMut::from_parse(&mut raw_img).unwrap();
+        image.adjust_contrast(0);
+        image.invert();
+        assert_eq!(image.pixel_data_mut(), [0xf0]);
+
+        let mut raw_img = *b"P4\n3 2\n\x40\xe0";
+        let mut image = PNMImageMut::from_parse(&mut raw_img).unwrap();
+        image.invert();
+        assert_eq!(image.pixel_data_mut(), [0xa0, 0x00]);
+        assert_eq!(
+            image.as_image(),
+            PNMImage::from_parse(b"P4\n3 2\n\xa0\x00").unwrap()
+        );
+    }
+
+    #[test]
+    fn flip_bitmap() {
+        let mut raw_img = *b"P4\n10 1\n\xc0\x40";
+        let mut image = PNMImageMut::from_parse(&mut raw_img).unwrap();
+        image.flip_horizontal_in_place();
+        assert_eq!(image.as_image().row_bits(0), Some(&[0x80, 0xc0][..]));
+    }
+}
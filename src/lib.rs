@@ -1,5 +1,89 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[macro_use]
+mod trace;
+
+mod align;
+mod ascii;
+#[cfg(feature = "base64")]
+mod base64;
+mod bitmap;
+mod blit;
+pub mod bundle;
+mod checksum;
+#[cfg(feature = "codegen")]
+pub mod codegen;
+mod columns;
+#[cfg(feature = "alloc")]
+mod cow;
+pub mod color;
+mod decode;
+mod encode;
+pub mod filter;
+mod frames;
+#[cfg(feature = "arbitrary")]
+pub mod fuzz;
+mod gray;
+mod image_mut;
+#[cfg(feature = "mmap")]
+mod mmap;
+#[cfg(feature = "alloc")]
+mod owned;
+mod pam;
+mod parallel;
+mod preview;
+mod quantize;
+mod read;
+#[cfg(feature = "async")]
+mod read_async;
+#[cfg(feature = "embedded-io")]
+mod read_blocking;
+#[cfg(feature = "std")]
+mod read_std;
+mod repair;
+mod rows;
+mod sample;
+mod scale;
+mod stats;
+#[cfg(feature = "embedded-storage")]
+mod storage;
+mod subimage;
+#[cfg(feature = "std")]
+pub mod testing;
+mod validate;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub use align::Word;
+pub use checksum::Checksum;
+#[cfg(feature = "alloc")]
+pub use cow::CowPNMImage;
+pub use decode::PixelFormat;
+pub use encode::{EncodeError, Encoding};
+pub use frames::{Frame, Frames};
+pub use gray::LumaWeights;
+pub use image_mut::PNMImageMut;
+#[cfg(feature = "mmap")]
+pub use mmap::MmapImage;
+#[cfg(feature = "alloc")]
+pub use owned::OwnedPNMImage;
+pub use quantize::QuantizeError;
+pub use read::ReadError;
+pub use repair::{Repairs, ShortData};
+#[cfg(feature = "std")]
+pub use read_std::RowReader;
+pub use sample::Sample;
+pub use stats::ChannelStats;
+#[cfg(feature = "embedded-storage")]
+pub use storage::StorageImage;
+#[cfg(feature = "async")]
+pub use read_async::AsyncRowReader;
+pub use subimage::SubImage;
+pub use validate::ValidationError;
+
 /// An enum that represents a PNM image
 #[derive(Clone, Debug)]
 pub enum PNMImage<'a> {
@@ -16,19 +100,104 @@ pub enum PNMImage<'a> {
         /// The pixel data of the image
         pixel_data: &'a [u8],
     },
+    /// Binary PGM (P5) image
+    PGMBinary {
+        /// The width of the image
+        width: usize,
+        /// The height of the image
+        height: usize,
+        /// The maximum gray value of the image
+        maximum_pixel: usize,
+        /// The comment associated with the image
+        comment: &'a str,
+        /// The pixel data of the image, one sample per pixel
+        pixel_data: &'a [u8],
+    },
+    /// Binary PBM (P4) image, its maximum pixel value is always 1
+    PBMBinary {
+        /// The width of the image
+        width: usize,
+        /// The height of the image
+        height: usize,
+        /// The comment associated with the image
+        comment: &'a str,
+        /// The pixel data of the image, one bit per pixel with every row padded to a whole byte
+        pixel_data: &'a [u8],
+    },
+    /// PAM (P7) image with an arbitrary number of samples per pixel
+    PAM {
+        /// The width of the image
+        width: usize,
+        /// The height of the image
+        height: usize,
+        /// The number of samples per pixel
+        depth: usize,
+        /// The maximum sample value of the image
+        maximum_pixel: usize,
+        /// The meaning of the samples, e.g. `RGB_ALPHA`, or an empty string if not given
+        tuple_type: &'a str,
+        /// The comment associated with the image
+        comment: &'a str,
+        /// The pixel data of the image, `depth` samples per pixel
+        pixel_data: &'a [u8],
+    },
 }
 
 use PNMImage::*;
 
+/// The format of a PNM image, matching the variants of [`PNMImage`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PNMFormat {
+    /// Binary PPM (P6)
+    PPM,
+    /// Binary PGM (P5)
+    PGM,
+    /// Binary PBM (P4)
+    PBM,
+    /// PAM (P7)
+    PAM,
+}
+
+/// The metadata shared by all PNM images, independent of their pixel data
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PNMHeader<'a> {
+    /// The format of the image
+    pub format: PNMFormat,
+    /// The width of the image
+    pub width: usize,
+    /// The height of the image
+    pub height: usize,
+    /// The number of samples per pixel, only used for PAM images
+    pub depth: usize,
+    /// The maximum pixel value of the image
+    pub maximum_pixel: usize,
+    /// The tuple type of the image, only used for PAM images
+    pub tuple_type: &'a str,
+    /// The comment associated with the image
+    pub comment: &'a str,
+}
+
 /// Error type that represents the different PNM parsing errors
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum PNMError {
     /// The file is not in PNM format
     NotPNMFormat,
-    /// The PNM format is not supported. Right now, only P6 is supported.
+    /// The PNM format is not supported. Right now, only P1 to P7 are supported.
     UnsupportedPNMFormat,
     /// Error while parsing a UTF-8 encoded string
     UTF8Error,
+    /// The data ended before the image was complete
+    UnexpectedEOF,
+    /// The scratch buffer is too small to hold the decoded pixel data
+    ScratchTooSmall {
+        /// The number of bytes the scratch buffer needs to hold
+        required: usize,
+    },
+    /// The base64 encoded image contains an invalid character or ends in a partial byte
+    InvalidBase64 {
+        /// The position of the invalid character
+        pos: usize,
+    },
     /// Error while parsing the image
     ParseError {
         /// The position of the error
@@ -37,14 +206,76 @@ pub enum PNMError {
         got: u8,
         /// Contextual information about the error
         ctx: &'static str,
+        /// What was expected instead of `got`
+        expected: Expected,
+        /// The part of the image that was being parsed
+        field: Field,
+        /// The header fields that were parsed successfully before the error
+        recovered: RecoveredHeader,
     },
 }
 
 use PNMError::*;
 
+/// What the parser expected when encountering an unexpected byte
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Expected {
+    /// A decimal digit
+    Digit,
+    /// A space separating two numbers
+    Space,
+    /// A newline ending a header line
+    Newline,
+    /// Whitespace ending a plain format sample
+    Whitespace,
+}
+
+/// The part of a PNM image being parsed when an error occurred
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Field {
+    /// The magic number, e.g. `P6`
+    Magic,
+    /// The width of the image
+    Width,
+    /// The height of the image
+    Height,
+    /// The maximum pixel value of the image
+    MaximumPixel,
+    /// The depth of a PAM image
+    Depth,
+    /// The pixel data of the image
+    PixelData,
+}
+
+/// The header fields successfully parsed before a parse error occurred
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RecoveredHeader {
+    /// The width of the image, if it was parsed
+    pub width: Option<usize>,
+    /// The height of the image, if it was parsed
+    pub height: Option<usize>,
+    /// The maximum pixel value of the image, if it was parsed
+    pub maximum_pixel: Option<usize>,
+}
+
+/// Error returned when a caller-provided output buffer is too small for the result
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BufferTooSmall {
+    /// The number of elements the buffer needs to hold
+    pub required: usize,
+}
+
+/// Decodes a single sample of one or two bytes, the latter being big-endian.
+fn sample_value(bytes: &[u8]) -> u16 {
+    match *bytes {
+        [hi, lo] => u16::from_be_bytes([hi, lo]),
+        _ => bytes[0] as u16,
+    }
+}
+
 impl<'a> PNMImage<'a> {
 
-    /// Parses a PNM image from a byte array
+    /// Parses a binary PNM image (P7, P6, P5 or P4) from a byte array
     ///
     /// # Arguments
     ///
@@ -53,53 +284,123 @@ impl<'a> PNMImage<'a> {
     /// # Returns
     ///
     /// A Result object containing the parsed PNMImage if successful, otherwise a PNMError
-    pub fn from_parse<const N: usize>(bytes: &'a [u8; N]) -> Result<Self, PNMError> {
-        // magic number P6\n
-        if bytes[0] != b'P' {
+    pub fn from_parse(bytes: &'a [u8]) -> Result<Self, PNMError> {
+        Self::parse(bytes).map(|(image, _)| image)
+    }
+
+    /// Parses a single PNM image from the front of a byte array, such as a stream of concatenated images
+    ///
+    /// Unlike [`from_parse`](Self::from_parse), the pixel data of the returned image is cut to
+    /// exactly the size described by its header.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - A byte array starting with the PNM image data
+    ///
+    /// # Returns
+    ///
+    /// A Result object containing the parsed PNMImage and the bytes following it if successful, otherwise a PNMError
+    pub fn from_parse_prefix(bytes: &'a [u8]) -> Result<(Self, &'a [u8]), PNMError> {
+        let (mut image, offset) = Self::parse(bytes)?;
+        let end = image
+            .raster_len()
+            .and_then(|len| offset.checked_add(len))
+            .filter(|&end| end <= bytes.len())
+            .ok_or(UnexpectedEOF)?;
+        trace!("pixel data cut to {} bytes", end - offset);
+        image.set_pixel_data(&bytes[offset..end]);
+        Ok((image, &bytes[end..]))
+    }
+
+    /// Replaces the pixel data of the image.
+    fn set_pixel_data(&mut self, data: &'a [u8]) {
+        let (PPMBinary{pixel_data, ..} | PGMBinary{pixel_data, ..} | PBMBinary{pixel_data, ..} | PAM{pixel_data, ..}) = self;
+        *pixel_data = data;
+    }
+
+    /// Parses the header and returns the image along with the offset of its pixel data.
+    /// The pixel data of the image spans all bytes following the header.
+    fn parse(bytes: &'a [u8]) -> Result<(Self, usize), PNMError> {
+        Self::parse_as(bytes, false)
+    }
+
+    /// Like [`parse`](Self::parse), but expects the magic number of a plain format (P3, P2 or P1) if `plain` is set.
+    fn parse_as(bytes: &'a [u8], plain: bool) -> Result<(Self, usize), PNMError> {
+        let byte = |idx: usize| bytes.get(idx).copied().ok_or(UnexpectedEOF);
+
+        // magic number P<magic>\n
+        if byte(0)? != b'P' {
             return Err(NotPNMFormat);
         }
-        match bytes[1] {
-            b'1' ..= b'5' => return Err(UnsupportedPNMFormat),
-            b'6' => (),
-            _ => return Err(NotPNMFormat)
+        let magic = byte(1)?;
+        if (magic, plain) == (b'7', false) {
+            return Self::parse_pam(bytes);
         }
-        if bytes[2] != b'\n' {
+        let channels = match (magic, plain) {
+            (b'6', false) | (b'3', true) => 3,
+            (b'5', false) | (b'2', true) => 1,
+            (b'4', false) | (b'1', true) => 0,
+            (b'1' ..= b'7', _) => return Err(UnsupportedPNMFormat),
+            _ => return Err(NotPNMFormat)
+        };
+        trace!("magic number P{}", magic - b'0');
+        if byte(2)? != b'\n' {
             return Err(ParseError {
                 pos: 2,
                 got: bytes[2],
                 ctx: "expected newline.",
+                expected: Expected::Newline,
+                field: Field::Magic,
+                recovered: RecoveredHeader::default(),
             });
         }
         let mut idx = 3;
 
-        // comments
-        while bytes[idx] == b'#' {
-            while bytes[idx] != b'\n' {
+        // comments, one or more lines starting with #
+        while byte(idx)? == b'#' {
+            while byte(idx)? != b'\n' {
                 idx += 1
             }
+            if byte(idx + 1)? != b'#' {
+                break;
+            }
+            idx += 1;
         }
         let comment = if idx == 3 {
             ""
         } else if let Ok(header) = core::str::from_utf8(&bytes[3..idx]) {
+            idx += 1;
             header
         } else {
             return Err(UTF8Error);
         };
-        idx += 1;
 
+        let mut recovered = RecoveredHeader::default();
         macro_rules! parse_dec {
-            ($stop:expr) => {{
-                let mut acc = 0;
-                while bytes[idx] != $stop {
+            ($stop:expr, $expected_stop:expr, $field:expr) => {{
+                let mut acc: usize = 0;
+                while byte(idx)? != $stop {
                     if !bytes[idx].is_ascii_digit() {
                         return Err(ParseError {
                             pos: idx,
                             got: bytes[idx],
                             ctx: "expected digit.",
+                            expected: Expected::Digit,
+                            field: $field,
+                            recovered,
                         });
                     }
-                    acc *= 10;
-                    acc += (bytes[idx] - b'0') as usize;
+                    acc = acc
+                        .checked_mul(10)
+                        .and_then(|acc| acc.checked_add((bytes[idx] - b'0') as usize))
+                        .ok_or(ParseError {
+                            pos: idx,
+                            got: bytes[idx],
+                            ctx: "number too large.",
+                            expected: $expected_stop,
+                            field: $field,
+                            recovered,
+                        })?;
 
                     idx += 1;
                 }
@@ -109,71 +410,296 @@ impl<'a> PNMImage<'a> {
         }
 
         // parse <width>SPC<height>\n
-        let width = parse_dec!(b' ');
-        let height = parse_dec!(b'\n');
-        // parse <maximum_pixel>\n
-        let maximum_pixel = parse_dec!(b'\n');
+        let width = parse_dec!(b' ', Expected::Space, Field::Width);
+        recovered.width = Some(width);
+        let height = parse_dec!(b'\n', Expected::Newline, Field::Height);
+        recovered.height = Some(height);
+        // parse <maximum_pixel>\n, bitmaps have none
+        let maximum_pixel = if channels == 0 {
+            1
+        } else {
+            parse_dec!(b'\n', Expected::Newline, Field::MaximumPixel)
+        };
+        trace!("header: width {}, height {}, maximum pixel {}, comment of {} bytes", width, height, maximum_pixel, comment.len());
+        trace!("pixel data at offset {}, {} bytes", idx, bytes.len() - idx);
 
         // rest is raw data
-        let pixel_data = &bytes[idx..N];
+        let pixel_data = &bytes[idx..];
 
-        Ok(Self::PPMBinary {
+        let image = match channels {
+            0 => Self::PBMBinary {
+                width,
+                height,
+                comment,
+                pixel_data,
+            },
+            1 => Self::PGMBinary {
+                width,
+                height,
+                maximum_pixel,
+                comment,
+                pixel_data,
+            },
+            _ => Self::PPMBinary {
+                width,
+                height,
+                maximum_pixel,
+                comment,
+                pixel_data,
+            },
+        };
+        Ok((image, idx))
+    }
+}
+
+impl<'a> PNMImage<'a> {
+    /// Creates an image from a header and raw pixel data, e.g. RGB data from a camera driver
+    ///
+    /// # Arguments
+    ///
+    /// * `header` - The header describing the pixel data
+    /// * `pixel_data` - The raw pixel data, laid out like that of an image of the format given by the header
+    ///
+    /// # Returns
+    ///
+    /// A Result object containing the PNMImage if it passes [`validate`](Self::validate), otherwise a ValidationError
+    pub fn from_raw_parts(header: PNMHeader<'a>, pixel_data: &'a [u8]) -> Result<Self, ValidationError> {
+        let image = Self::from_raw_parts_unchecked(header, pixel_data);
+        image.validate()?;
+        Ok(image)
+    }
+
+    /// Creates an image from a header and raw pixel data without checking that they match.
+    ///
+    /// This is not unsafe, as all accessors are bounds checked, but pixels missing from
+    /// `pixel_data` will read as `None`. The variant is chosen by the format of `header`.
+    pub fn from_raw_parts_unchecked(header: PNMHeader<'a>, pixel_data: &'a [u8]) -> Self {
+        let PNMHeader{format, width, height, depth, maximum_pixel, tuple_type, comment} = header;
+        match format {
+            PNMFormat::PPM => PPMBinary {
+                width,
+                height,
+                maximum_pixel,
+                comment,
+                pixel_data,
+            },
+            PNMFormat::PGM => PGMBinary {
+                width,
+                height,
+                maximum_pixel,
+                comment,
+                pixel_data,
+            },
+            PNMFormat::PBM => PBMBinary {
+                width,
+                height,
+                comment,
+                pixel_data,
+            },
+            PNMFormat::PAM => PAM {
+                width,
+                height,
+                depth,
+                maximum_pixel,
+                tuple_type,
+                comment,
+                pixel_data,
+            },
+        }
+    }
+
+    /// Splits the image into its header and raw pixel data.
+    pub fn into_raw_parts(self) -> (PNMHeader<'a>, &'a [u8]) {
+        let (PPMBinary{pixel_data, ..} | PGMBinary{pixel_data, ..} | PBMBinary{pixel_data, ..} | PAM{pixel_data, ..}) = self;
+        (self.header(), pixel_data)
+    }
+
+    /// Returns the header of the PNM image.
+    ///
+    /// The depth and tuple type of images other than PAM are the ones implied by their format.
+    pub fn header(&self) -> PNMHeader<'a> {
+        let (format, width, height, depth, maximum_pixel, tuple_type, comment) = match *self {
+            PPMBinary{width, height, maximum_pixel, comment, ..} => (PNMFormat::PPM, width, height, 3, maximum_pixel, "RGB", comment),
+            PGMBinary{width, height, maximum_pixel, comment, ..} => (PNMFormat::PGM, width, height, 1, maximum_pixel, "GRAYSCALE", comment),
+            PBMBinary{width, height, comment, ..} => (PNMFormat::PBM, width, height, 1, 1, "BLACKANDWHITE", comment),
+            PAM{width, height, depth, maximum_pixel, tuple_type, comment, ..} => (PNMFormat::PAM, width, height, depth, maximum_pixel, tuple_type, comment),
+        };
+        PNMHeader {
+            format,
             width,
             height,
+            depth,
             maximum_pixel,
+            tuple_type,
             comment,
-            pixel_data,
-        })
+        }
     }
 }
 
 impl PNMImage<'_> {
+    /// Returns the number of bytes per sample, which is 2 for images with a maximum pixel value above 255.
+    fn sample_size(&self) -> usize {
+        if self.maximum_pixel() > 255 {
+            2
+        } else {
+            1
+        }
+    }
+
+    /// Returns the number of samples per pixel, which is 1 for grayscale images and bitmaps.
+    fn channels(&self) -> usize {
+        match self {
+            PPMBinary{..} => 3,
+            PGMBinary{..} | PBMBinary{..} => 1,
+            PAM{depth, ..} => *depth,
+        }
+    }
+
+    /// Returns whether this is a bitmap, whose pixels take a single bit each.
+    fn is_bitmap(&self) -> bool {
+        matches!(self, PBMBinary{..})
+    }
+
+    /// Returns the number of pixel data bytes per pixel. Only meaningful for images that are not bitmaps.
+    fn pixel_size(&self) -> usize {
+        self.channels() * self.sample_size()
+    }
+
+    /// Returns the number of pixel data bytes per row, or `None` on overflow.
+    fn row_len(&self) -> Option<usize> {
+        if self.is_bitmap() {
+            Some(self.width().div_ceil(8))
+        } else {
+            self.width().checked_mul(self.pixel_size())
+        }
+    }
+
+    /// Returns the number of pixel data bytes described by the header, or `None` on overflow.
+    fn raster_len(&self) -> Option<usize> {
+        self.row_len()?.checked_mul(self.height())
+    }
+
     /// Returns the width of the PNM image.
     pub fn width(&self) -> usize {
-        let PPMBinary{width, ..} = *self;
-        width
+        self.header().width
     }
 
     /// Returns the height of the PNM image.
     pub fn height(&self) -> usize {
-        let PPMBinary{height, ..} = *self;
-        height
+        self.header().height
     }
 
     /// Returns the maximum pixel value of the PNM image.
     pub fn maximum_pixel(&self) -> usize {
-        let PPMBinary{maximum_pixel, ..} = *self;
-        maximum_pixel
+        self.header().maximum_pixel
     }
 
     /// Returns the comment associated with the PNM image.
+    ///
+    /// This is the raw comment block, including the leading `#` of every line and the line breaks
+    /// between them, so it can be written back unchanged. Use [`comments`](Self::comments) to read its text.
     pub fn comment(&self) -> &str {
-        let PPMBinary{comment, ..} = *self;
-        comment
+        self.header().comment
+    }
+
+    /// Returns an iterator over the lines of the comment, with the leading `#` and surrounding whitespace stripped.
+    pub fn comments(&self) -> impl Iterator<Item = &str> {
+        self.comment()
+            .lines()
+            .map(|line| line.strip_prefix('#').unwrap_or(line).trim())
+    }
+
+    /// Returns an iterator over the `key=value` pairs stored in the comment, one per line, e.g. `# exposure=12ms`.
+    ///
+    /// Keys and values have surrounding whitespace stripped. Comment lines without `=` or with an empty key
+    /// or a key containing whitespace are skipped.
+    pub fn metadata(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.comments()
+            .filter_map(|line| line.split_once('='))
+            .map(|(key, value)| (key.trim(), value.trim()))
+            .filter(|(key, _)| !key.is_empty() && !key.contains(char::is_whitespace))
+    }
+
+    /// Returns the value of the first metadata entry with the given key, see [`metadata`](Self::metadata).
+    pub fn metadata_value(&self, key: &str) -> Option<&str> {
+        self.metadata().find(|&(k, _)| k == key).map(|(_, value)| value)
     }
 
     /// Returns the raw pixel bytes data of the PNM image.
     fn pixel_data(&self) -> &[u8] {
-        let PPMBinary{pixel_data, ..} = *self;
+        let (PPMBinary{pixel_data, ..} | PGMBinary{pixel_data, ..} | PBMBinary{pixel_data, ..} | PAM{pixel_data, ..}) = *self;
         pixel_data
     }
 
+    /// Returns the pixel data as a slice of RGB triples, one per pixel in row-major order.
+    ///
+    /// This is a zero-cost view of the raw bytes. Returns `None` if the image is not an RGB image,
+    /// has 16-bit samples or holds less pixel data than its header describes.
+    pub fn as_rgb_triples(&self) -> Option<&[[u8; 3]]> {
+        if self.is_bitmap() || self.pixel_size() != 3 {
+            return None;
+        }
+        let data = self.pixel_data().get(..self.raster_len()?)?;
+        // SAFETY: `[u8; 3]` has the same alignment as `u8` and no invalid bit patterns,
+        // and `data` holds exactly `data.len() / 3` triples as its length is a multiple of 3.
+        let triples = unsafe {
+            core::slice::from_raw_parts(data.as_ptr().cast::<[u8; 3]>(), data.len() / 3)
+        };
+        Some(triples)
+    }
+
     /// Returns the RGB values of the pixel at the specified (x, y) coordinate.
     /// Returns `None` if the pixel is outside the bounds of the image.
+    /// The gray value of grayscale images is replicated to all three channels.
+    ///
+    /// Samples are scaled from `0..=maximum_pixel` to `0..=255`, so white pixels of bitmaps read as 255.
+    /// Use [`pixel_rgb16`](Self::pixel_rgb16) to read the raw samples.
     pub fn pixel_rgb(&self, x: usize, y: usize) -> Option<(u8, u8, u8)> {
-        let idx = (x + y * self.width()) * 3;
-        if idx >= self.pixel_data().len() {
-            None
-        } else {
-            Some((
-                self.pixel_data()[idx],
-                self.pixel_data()[idx + 1],
-                self.pixel_data()[idx + 2],
-            ))
-        }
+        self.pixel::<u8>(x, y)
+    }
+
+    /// Returns the RGB values of the pixel at the specified (x, y) coordinate as 16-bit samples.
+    /// Returns `None` if the pixel is outside the bounds of the image.
+    ///
+    /// Samples of images with a maximum pixel value above 255 are decoded as big-endian values,
+    /// samples of other images are widened without scaling.
+    pub fn pixel_rgb16(&self, x: usize, y: usize) -> Option<(u16, u16, u16)> {
+        self.pixel::<u16>(x, y)
+    }
+
+    /// Returns the pixel data described by the header, without any trailing bytes.
+    /// Falls back to all pixel data if the image holds less than its header describes.
+    fn raster(&self) -> &[u8] {
+        let pixel_data = self.pixel_data();
+        self.raster_len()
+            .and_then(|len| pixel_data.get(..len))
+            .unwrap_or(pixel_data)
+    }
+
+    /// Compares two images like `==`, but also requires their comments to be equal.
+    pub fn eq_with_comment(&self, other: &PNMImage<'_>) -> bool {
+        self == other && self.comment() == other.comment()
     }
 }
 
+/// Images are equal if their formats, dimensions, maximum pixel values and pixel data match.
+///
+/// Comments and bytes trailing the pixel data are ignored,
+/// use [`eq_with_comment`](PNMImage::eq_with_comment) to compare comments as well.
+impl PartialEq<PNMImage<'_>> for PNMImage<'_> {
+    fn eq(&self, other: &PNMImage<'_>) -> bool {
+        core::mem::discriminant(self) == core::mem::discriminant(other)
+            && self.depth() == other.depth()
+            && self.tuple_type() == other.tuple_type()
+            && self.width() == other.width()
+            && self.height() == other.height()
+            && self.maximum_pixel() == other.maximum_pixel()
+            && self.raster() == other.raster()
+    }
+}
+
+impl Eq for PNMImage<'_> {}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -212,4 +738,194 @@ mod test {
         assert_eq!(ppm_img.pixel_rgb(31, 56), Some((255,0,0)));
         assert_eq!(ppm_img.pixel_rgb(56, 56), Some((0,0,255)));
     }
+
+    #[test]
+    fn test_header() {
+        let raw_img = include_bytes!("./binary.ppm");
+        let header = PNMImage::from_parse(raw_img).unwrap().header();
+
+        assert_eq!(header, PNMHeader {
+            format: PNMFormat::PPM,
+            width: 64,
+            height: 64,
+            depth: 3,
+            maximum_pixel: 255,
+            tuple_type: "RGB",
+            comment: "# Created by GIMP version 2.10.34 PNM plug-in",
+        });
+        let (prefix_img, _) = PNMImage::from_parse_prefix(raw_img).unwrap();
+        assert_eq!(prefix_img.header(), header);
+    }
+
+    #[test]
+    fn test_pixel_rgb16() {
+        let raw_img = include_bytes!("./binary.ppm");
+        let ppm_img = PNMImage::from_parse(raw_img).unwrap();
+        assert_eq!(ppm_img.pixel_rgb16(56, 7), Some((255,255,0)));
+        assert_eq!(ppm_img.pixel_rgb16(64, 63), None);
+
+        let deep_img = PNMImage::from_parse(b"P6\n1 1\n4095\n\x0f\xff\x01\x00\x00\x80").unwrap();
+        assert_eq!(deep_img.pixel_rgb16(0, 0), Some((4095,256,128)));
+        assert_eq!(deep_img.pixel_rgb16(1, 0), None);
+    }
+
+    #[test]
+    fn test_as_rgb_triples() {
+        let raw_img = include_bytes!("./binary.ppm");
+        let ppm_img = PNMImage::from_parse(raw_img).unwrap();
+        let triples = ppm_img.as_rgb_triples().unwrap();
+        assert_eq!(triples.len(), 64 * 64);
+        assert_eq!(triples[7 * 64 + 31], [255, 0, 0]);
+        assert_eq!(triples[56 * 64 + 56], [0, 0, 255]);
+
+        let short_img = PNMImage::from_parse(b"P6\n2 1\n255\n\x01\x02\x03\x04").unwrap();
+        assert_eq!(short_img.as_rgb_triples(), None);
+        let deep_img = PNMImage::from_parse(b"P6\n1 1\n65535\n\x00\x00\x00\x00\x00\x00").unwrap();
+        assert_eq!(deep_img.as_rgb_triples(), None);
+    }
+
+    #[test]
+    fn test_raw_parts() {
+        let raw_img = include_bytes!("./binary.ppm");
+        let (header, pixel_data) = PNMImage::from_parse(raw_img).unwrap().into_raw_parts();
+        assert_eq!(header.width, 64);
+        assert_eq!(pixel_data.len(), 64 * 64 * 3);
+
+        let ppm_img = PNMImage::from_raw_parts(header, pixel_data).unwrap();
+        assert_eq!(ppm_img.pixel_rgb(31, 7), Some((255,0,0)));
+
+        let camera = PNMHeader {
+            format: PNMFormat::PPM,
+            width: 2,
+            height: 1,
+            depth: 3,
+            maximum_pixel: 255,
+            tuple_type: "RGB",
+            comment: "",
+        };
+        assert_eq!(
+            PNMImage::from_raw_parts(camera, &[1, 2, 3]).unwrap_err(),
+            ValidationError::DataLengthMismatch { expected: 6, actual: 3 }
+        );
+        let unchecked = PNMImage::from_raw_parts_unchecked(camera, &[1, 2, 3]);
+        assert_eq!(unchecked.pixel_rgb(0, 0), Some((1,2,3)));
+        assert_eq!(unchecked.pixel_rgb(1, 0), None);
+
+        let pam_img: &[u8] = b"P7\nWIDTH 1\nHEIGHT 1\nDEPTH 2\nMAXVAL 255\nTUPLTYPE GRAYSCALE_ALPHA\nENDHDR\n\x10\x20";
+        let images: [&[u8]; 4] = [raw_img, b"P5\n2 1\n65535\n\x01\x02\x03\x04", b"P4\n9 1\n\x80\x80", pam_img];
+        for raw_img in images {
+            let image = PNMImage::from_parse(raw_img).unwrap();
+            let (header, pixel_data) = image.clone().into_raw_parts();
+            let rebuilt = PNMImage::from_raw_parts(header, pixel_data).unwrap();
+            assert_eq!(rebuilt, image);
+            assert_eq!(rebuilt.header(), image.header());
+        }
+
+        let flat = PNMHeader{format: PNMFormat::PAM, depth: 0, ..camera};
+        assert_eq!(PNMImage::from_raw_parts(flat, &[]).unwrap_err(), ValidationError::ZeroDimension);
+    }
+
+    #[test]
+    fn test_eq() {
+        let raw_img = include_bytes!("./binary.ppm");
+        let ppm_img = PNMImage::from_parse(raw_img).unwrap();
+        let (header, pixel_data) = ppm_img.clone().into_raw_parts();
+        let uncommented = PNMImage::from_raw_parts_unchecked(PNMHeader{comment: "", ..header}, pixel_data);
+
+        assert_eq!(ppm_img, uncommented);
+        assert!(!ppm_img.eq_with_comment(&uncommented));
+        assert!(ppm_img.eq_with_comment(&ppm_img.clone()));
+
+        let mut changed = *raw_img;
+        changed[changed.len() - 1] ^= 1;
+        assert_ne!(ppm_img, PNMImage::from_parse(&changed).unwrap());
+
+        let trailing = PNMImage::from_parse(b"P6\n1 1\n255\n\x01\x02\x03\n").unwrap();
+        assert_eq!(trailing, PNMImage::from_parse(b"P6\n# c\n1 1\n255\n\x01\x02\x03").unwrap());
+    }
+
+    #[test]
+    fn test_comments() {
+        let raw_img = b"P6\n# first line\n#second\n#\n1 1\n255\n\x01\x02\x03";
+        let ppm_img = PNMImage::from_parse(raw_img).unwrap();
+        assert_eq!(ppm_img.comment(), "# first line\n#second\n#");
+        let mut comments = ppm_img.comments();
+        assert_eq!(comments.next(), Some("first line"));
+        assert_eq!(comments.next(), Some("second"));
+        assert_eq!(comments.next(), Some(""));
+        assert_eq!(comments.next(), None);
+        assert_eq!(ppm_img.pixel_rgb(0, 0), Some((1,2,3)));
+
+        let single = PNMImage::from_parse(include_bytes!("./binary.ppm")).unwrap();
+        assert!(single.comments().eq(["Created by GIMP version 2.10.34 PNM plug-in"]));
+    }
+
+    #[test]
+    fn test_parse_without_comment() {
+        let ppm_img = PNMImage::from_parse(b"P6\n2 1\n255\n\x01\x02\x03\x04\x05\x06").unwrap();
+
+        assert_eq!(ppm_img.comment(), "");
+        assert_eq!(ppm_img.width(), 2);
+        assert_eq!(ppm_img.height(), 1);
+        assert_eq!(ppm_img.maximum_pixel(), 255);
+        assert_eq!(ppm_img.pixel_rgb(1, 0), Some((4,5,6)));
+    }
+
+    #[test]
+    fn test_truncated() {
+        assert_eq!(PNMImage::from_parse(b"").unwrap_err(), UnexpectedEOF);
+        assert_eq!(PNMImage::from_parse(b"P6\n# comment").unwrap_err(), UnexpectedEOF);
+        assert_eq!(PNMImage::from_parse(b"P6\n2 1\n25").unwrap_err(), UnexpectedEOF);
+        assert_eq!(
+            PNMImage::from_parse_prefix(b"P6\n2 1\n255\n\x01\x02\x03").unwrap_err(),
+            UnexpectedEOF
+        );
+        assert_eq!(
+            PNMImage::from_parse(b"P6\n99999999999999999999999 1\n255\n").unwrap_err(),
+            ParseError {
+                pos: 22,
+                got: b'9',
+                ctx: "number too large.",
+                expected: Expected::Space,
+                field: Field::Width,
+                recovered: RecoveredHeader::default(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_errors_compare() {
+        assert_eq!(PNMImage::from_parse(b"X6\n").unwrap_err(), NotPNMFormat);
+        assert_eq!(PNMImage::from_parse(b"P3\n").unwrap_err(), UnsupportedPNMFormat);
+        assert_ne!(UnexpectedEOF, UTF8Error);
+        assert_ne!(
+            ScratchTooSmall { required: 1 },
+            ScratchTooSmall { required: 2 }
+        );
+    }
+
+    #[test]
+    fn test_parse_error_details() {
+        match PNMImage::from_parse(b"P6\n# comment\n64 6x\n255\n") {
+            Err(ParseError { pos: 17, got: b'x', expected, field, recovered, .. }) => {
+                assert_eq!(expected, Expected::Digit);
+                assert_eq!(field, Field::Height);
+                assert_eq!(recovered.width, Some(64));
+                assert_eq!(recovered.height, None);
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+        match PNMImage::from_parse(b"P6\n2 2\n255 \n") {
+            Err(ParseError { expected, field, recovered, .. }) => {
+                assert_eq!(expected, Expected::Digit);
+                assert_eq!(field, Field::MaximumPixel);
+                assert_eq!(recovered, RecoveredHeader {
+                    width: Some(2),
+                    height: Some(2),
+                    maximum_pixel: None,
+                });
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
 }
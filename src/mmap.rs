@@ -0,0 +1,151 @@
+use std::fs::File;
+use std::io;
+use std::ops::Range;
+use std::path::Path;
+
+use memmap2::Mmap;
+
+use crate::{PNMHeader, PNMImage, ReadError};
+
+/// A PNM image backed by a memory-mapped file, created by [`PNMImage::open_mmap`]
+///
+/// The mapping is kept alive for as long as the handle exists, and pixels are only paged in
+/// from the file when they are accessed.
+#[derive(Debug)]
+pub struct MmapImage {
+    map: Mmap,
+    header: PNMHeader<'static>,
+    /// The positions of the tuple type and comment within the map
+    tuple_type: Range<usize>,
+    comment: Range<usize>,
+    /// The offset of the pixel data within the map
+    offset: usize,
+}
+
+impl PNMImage<'_> {
+    /// Maps the PNM image file at `path` into memory and parses it
+    ///
+    /// The pixel data is never copied, so only the pages holding the pixels that are accessed
+    /// are read from disk.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the image file
+    ///
+    /// # Returns
+    ///
+    /// A Result object containing the MmapImage if successful, otherwise a ReadError
+    ///
+    /// # Safety
+    ///
+    /// Like [`Mmap::map`], this is undefined behavior if the file is modified or truncated,
+    /// by this or any other process, while the returned [`MmapImage`] exists.
+    pub unsafe fn open_mmap(path: impl AsRef<Path>) -> Result<MmapImage, ReadError<io::Error>> {
+        let file = File::open(path).map_err(ReadError::Io)?;
+        // SAFETY: the caller guarantees that the file is not modified while it is mapped
+        let map = unsafe { Mmap::map(&file) }.map_err(ReadError::Io)?;
+        let image = PNMImage::from_parse(&map)?;
+
+        // the strings of the header borrow from the map, so their positions follow from their addresses
+        let range = |s: &str| match s.len() {
+            0 => 0..0,
+            len => {
+                let start = s.as_ptr() as usize - map.as_ptr() as usize;
+                start..start + len
+            }
+        };
+        let tuple_type = match image {
+            PNMImage::PAM { tuple_type, .. } => range(tuple_type),
+            _ => 0..0,
+        };
+        let header = image.header();
+        let comment = range(header.comment);
+        let offset = map.len() - image.pixel_data().len();
+        let header = PNMHeader {
+            tuple_type: "",
+            comment: "",
+            ..header
+        };
+        Ok(MmapImage {
+            map,
+            header,
+            tuple_type,
+            comment,
+            offset,
+        })
+    }
+}
+
+impl MmapImage {
+    /// Returns a view of the image, giving access to all accessors of [`PNMImage`].
+    pub fn as_image(&self) -> PNMImage<'_> {
+        // both strings were checked to be valid UTF-8 when the image was opened
+        let str_at = |range: &Range<usize>| {
+            core::str::from_utf8(&self.map[range.clone()]).unwrap_or_default()
+        };
+        let header = PNMHeader {
+            tuple_type: str_at(&self.tuple_type),
+            comment: str_at(&self.comment),
+            ..self.header
+        };
+        PNMImage::from_raw_parts_unchecked(header, &self.map[self.offset..])
+    }
+
+    /// Returns the header of the image.
+    ///
+    /// Like [`PNMImage::header`], the tuple type of PPM, PGM and PBM images is implied by their format.
+    pub fn header(&self) -> PNMHeader<'_> {
+        self.as_image().header()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn open_mmap() {
+        // a path of its own keeps concurrent test runs from clobbering the file
+        let name = format!("micropnm_open_mmap_{}.ppm", std::process::id());
+        let path = std::env::temp_dir().join(name);
+        std::fs::write(&path, include_bytes!("./binary.ppm")).unwrap();
+        // SAFETY: the file is only rewritten after the mapping is dropped
+        let mapped = unsafe { PNMImage::open_mmap(&path) }.unwrap();
+        assert_eq!(mapped.header().width, 64);
+        assert_eq!(mapped.header().tuple_type, "RGB");
+        assert_eq!(mapped.as_image().pixel_rgb(56, 56), Some((0, 0, 255)));
+        assert_eq!(
+            mapped.as_image(),
+            PNMImage::from_parse(include_bytes!("./binary.ppm")).unwrap()
+        );
+        assert_eq!(
+            mapped.header().comment,
+            "# Created by GIMP version 2.10.34 PNM plug-in"
+        );
+        drop(mapped);
+
+        std::fs::write(
+            &path,
+            b"P7\nWIDTH 1\nHEIGHT 1\nDEPTH 2\nMAXVAL 255\nTUPLTYPE GRAYSCALE_ALPHA\nENDHDR\n\x10\x20",
+        )
+        .unwrap();
+        let mapped = unsafe { PNMImage::open_mmap(&path) }.unwrap();
+        assert_eq!(mapped.header().tuple_type, "GRAYSCALE_ALPHA");
+        assert_eq!(
+            mapped.as_image().pixel_rgba(0, 0),
+            Some((0x10, 0x10, 0x10, 0x20))
+        );
+        drop(mapped);
+
+        std::fs::write(&path, b"P6\n1 1\n").unwrap();
+        assert!(matches!(
+            unsafe { PNMImage::open_mmap(&path) },
+            Err(ReadError::Parse(crate::PNMError::UnexpectedEOF))
+        ));
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(
+            unsafe { PNMImage::open_mmap(&path) },
+            Err(ReadError::Io(_))
+        ));
+    }
+}
@@ -0,0 +1,230 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{PNMHeader, PNMImage};
+
+/// The format of an [`OwnedPNMImage`], matching the variants of [`PNMImage`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+enum Format {
+    Ppm,
+    Pgm,
+    Pbm,
+    Pam,
+}
+
+/// A PNM image owning its comment and pixel data, created by [`PNMImage::into_owned`]
+///
+/// With the `serde` feature, owned images can be serialized, with the pixel data stored as bytes.
+/// Deserialized images are checked to be consistent, so untrusted input cannot cause panics later.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "RawOwnedPNMImage")
+)]
+pub struct OwnedPNMImage {
+    format: Format,
+    width: usize,
+    height: usize,
+    depth: usize,
+    maximum_pixel: usize,
+    tuple_type: String,
+    comment: String,
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
+    pixel_data: Vec<u8>,
+}
+
+/// The fields of a deserialized [`OwnedPNMImage`] before they are checked
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct RawOwnedPNMImage {
+    format: Format,
+    width: usize,
+    height: usize,
+    depth: usize,
+    maximum_pixel: usize,
+    tuple_type: String,
+    comment: String,
+    #[serde(with = "serde_bytes")]
+    pixel_data: Vec<u8>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawOwnedPNMImage> for OwnedPNMImage {
+    type Error = &'static str;
+
+    fn try_from(raw: RawOwnedPNMImage) -> Result<Self, Self::Error> {
+        let depth_matches = match raw.format {
+            Format::Ppm => raw.depth == 3,
+            Format::Pgm | Format::Pbm => raw.depth == 1,
+            Format::Pam => raw.depth >= 1,
+        };
+        if !depth_matches {
+            return Err("depth does not match the format");
+        }
+        let maximum_pixel_matches = match raw.format {
+            Format::Pbm => raw.maximum_pixel == 1,
+            _ => (1..=65535).contains(&raw.maximum_pixel),
+        };
+        if !maximum_pixel_matches {
+            return Err("maximum pixel value out of range");
+        }
+        let image = OwnedPNMImage {
+            format: raw.format,
+            width: raw.width,
+            height: raw.height,
+            depth: raw.depth,
+            maximum_pixel: raw.maximum_pixel,
+            tuple_type: raw.tuple_type,
+            comment: raw.comment,
+            pixel_data: raw.pixel_data,
+        };
+        if image.as_image().raster_len() != Some(image.pixel_data.len()) {
+            return Err("pixel data does not match the dimensions");
+        }
+        Ok(image)
+    }
+}
+
+impl PNMImage<'_> {
+    /// Copies the comment and pixel data of the image, so it no longer borrows its source.
+    ///
+    /// Trailing bytes after the pixel data described by the header are not copied.
+    pub fn into_owned(self) -> OwnedPNMImage {
+        let format = match self {
+            PNMImage::PPMBinary { .. } => Format::Ppm,
+            PNMImage::PGMBinary { .. } => Format::Pgm,
+            PNMImage::PBMBinary { .. } => Format::Pbm,
+            PNMImage::PAM { .. } => Format::Pam,
+        };
+        let tuple_type = match self {
+            PNMImage::PAM { tuple_type, .. } => tuple_type.into(),
+            _ => String::new(),
+        };
+        OwnedPNMImage {
+            format,
+            width: self.width(),
+            height: self.height(),
+            depth: self.depth(),
+            maximum_pixel: self.maximum_pixel(),
+            tuple_type,
+            comment: self.comment().into(),
+            pixel_data: self.raster().into(),
+        }
+    }
+}
+
+impl OwnedPNMImage {
+    /// Returns a view of the image, giving access to all accessors of [`PNMImage`].
+    pub fn as_image(&self) -> PNMImage<'_> {
+        let (width, height, maximum_pixel) = (self.width, self.height, self.maximum_pixel);
+        let (comment, pixel_data) = (self.comment.as_str(), self.pixel_data.as_slice());
+        match self.format {
+            Format::Ppm => PNMImage::PPMBinary {
+                width,
+                height,
+                maximum_pixel,
+                comment,
+                pixel_data,
+            },
+            Format::Pgm => PNMImage::PGMBinary {
+                width,
+                height,
+                maximum_pixel,
+                comment,
+                pixel_data,
+            },
+            Format::Pbm => PNMImage::PBMBinary {
+                width,
+                height,
+                comment,
+                pixel_data,
+            },
+            Format::Pam => PNMImage::PAM {
+                width,
+                height,
+                depth: self.depth,
+                maximum_pixel,
+                tuple_type: &self.tuple_type,
+                comment,
+                pixel_data,
+            },
+        }
+    }
+
+    /// Returns the header of the image.
+    pub fn header(&self) -> PNMHeader<'_> {
+        self.as_image().header()
+    }
+
+    /// Returns the raw pixel data of the image.
+    pub fn pixel_data_mut(&mut self) -> &mut [u8] {
+        &mut self.pixel_data
+    }
+}
+
+impl From<PNMImage<'_>> for OwnedPNMImage {
+    fn from(image: PNMImage<'_>) -> Self {
+        image.into_owned()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn into_owned() {
+        let raw_img = include_bytes!("./binary.ppm");
+        let mut owned = PNMImage::from_parse(raw_img).unwrap().into_owned();
+        assert_eq!(owned.as_image(), PNMImage::from_parse(raw_img).unwrap());
+        assert_eq!(
+            owned.header().comment,
+            "# Created by GIMP version 2.10.34 PNM plug-in"
+        );
+        owned.pixel_data_mut()[..3].copy_from_slice(&[1, 2, 3]);
+        assert_eq!(owned.as_image().pixel_rgb(0, 0), Some((1, 2, 3)));
+
+        let pam_img = PNMImage::from_parse(
+            b"P7\nWIDTH 1\nHEIGHT 1\nDEPTH 2\nMAXVAL 255\nTUPLTYPE GRAYSCALE_ALPHA\nENDHDR\n\x10\x20\xff",
+        )
+        .unwrap();
+        let owned = OwnedPNMImage::from(pam_img.clone());
+        assert_eq!(owned.as_image().tuple_type(), "GRAYSCALE_ALPHA");
+        assert_eq!(owned.as_image().pixel_data(), [0x10, 0x20]);
+        assert_eq!(owned.as_image(), pam_img);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        let raw_img = b"P5\n2 1\n255\n\x01\x02";
+        let owned = PNMImage::from_parse(raw_img).unwrap().into_owned();
+        let bytes = postcard::to_allocvec(&owned).unwrap();
+        // the pixel data is stored as a length-prefixed byte string
+        assert!(bytes.ends_with(&[2, 1, 2]));
+        let decoded: OwnedPNMImage = postcard::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded, owned);
+
+        let flat = OwnedPNMImage {
+            format: Format::Pam,
+            depth: 0,
+            ..owned.clone()
+        };
+        let bytes = postcard::to_allocvec(&flat).unwrap();
+        assert!(postcard::from_bytes::<OwnedPNMImage>(&bytes).is_err());
+        let deep = OwnedPNMImage {
+            maximum_pixel: 65535,
+            ..owned.clone()
+        };
+        let bytes = postcard::to_allocvec(&deep).unwrap();
+        assert!(postcard::from_bytes::<OwnedPNMImage>(&bytes).is_err());
+        let color = OwnedPNMImage {
+            format: Format::Ppm,
+            ..owned
+        };
+        let bytes = postcard::to_allocvec(&color).unwrap();
+        assert!(postcard::from_bytes::<OwnedPNMImage>(&bytes).is_err());
+    }
+}
@@ -0,0 +1,278 @@
+use crate::PNMError::{self, *};
+use crate::PNMImage::{self, *};
+use crate::{Expected, Field, RecoveredHeader, Sample};
+
+impl<'a> PNMImage<'a> {
+    /// Parses the header of a PAM image (P7) and returns the image along with the offset of its pixel data.
+    ///
+    /// Only the comment lines directly following the magic number are kept, like for the other formats.
+    /// If the tuple type is given more than once, the first one is kept.
+    pub(crate) fn parse_pam(bytes: &'a [u8]) -> Result<(Self, usize), PNMError> {
+        if bytes.get(2).ok_or(UnexpectedEOF)? != &b'\n' {
+            return Err(ParseError {
+                pos: 2,
+                got: bytes[2],
+                ctx: "expected newline.",
+                expected: Expected::Newline,
+                field: Field::Magic,
+                recovered: RecoveredHeader::default(),
+            });
+        }
+
+        let mut recovered = RecoveredHeader::default();
+        let mut depth = None;
+        let mut tuple_type = None;
+        let mut comment = None;
+        let mut idx = 3;
+        loop {
+            let len = bytes[idx..]
+                .iter()
+                .position(|&b| b == b'\n')
+                .ok_or(UnexpectedEOF)?;
+            let raw_line = core::str::from_utf8(&bytes[idx..idx + len]).map_err(|_| UTF8Error)?;
+            let start = idx;
+            idx += len + 1;
+
+            if raw_line.starts_with('#') {
+                // extend the comment block as long as no other line came before
+                if start == 3 || comment.is_some_and(|(_, end)| end + 1 == start) {
+                    let from = comment.map_or(start, |(from, _)| from);
+                    comment = Some((from, start + len));
+                }
+                continue;
+            }
+            let line = raw_line.trim();
+            if line == "ENDHDR" {
+                break;
+            }
+            let (key, value) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            let value = value.trim_start();
+            // `value` ends where the trimmed line ends
+            let leading = raw_line.len() - raw_line.trim_start().len();
+            let value_pos = start + leading + line.len() - value.len();
+            let field = match key {
+                "WIDTH" => Field::Width,
+                "HEIGHT" => Field::Height,
+                "DEPTH" => Field::Depth,
+                "MAXVAL" => Field::MaximumPixel,
+                "TUPLTYPE" => {
+                    tuple_type = tuple_type.or(Some(value));
+                    continue;
+                }
+                // unknown header lines are ignored
+                _ => continue,
+            };
+            let number = parse_number(value, value_pos, field, recovered)?;
+            match field {
+                Field::Width => recovered.width = Some(number),
+                Field::Height => recovered.height = Some(number),
+                Field::MaximumPixel => recovered.maximum_pixel = Some(number),
+                _ => depth = Some(number),
+            }
+        }
+
+        let missing = |field| ParseError {
+            pos: idx - 7,
+            got: b'E',
+            ctx: "missing header field.",
+            expected: Expected::Digit,
+            field,
+            recovered,
+        };
+        let width = recovered.width.ok_or_else(|| missing(Field::Width))?;
+        let height = recovered.height.ok_or_else(|| missing(Field::Height))?;
+        let depth = depth.ok_or_else(|| missing(Field::Depth))?;
+        let maximum_pixel = recovered
+            .maximum_pixel
+            .ok_or_else(|| missing(Field::MaximumPixel))?;
+        let comment = match comment {
+            Some((from, to)) => core::str::from_utf8(&bytes[from..to]).map_err(|_| UTF8Error)?,
+            None => "",
+        };
+        trace!(
+            "header: width {}, height {}, depth {}, maximum pixel {}, comment of {} bytes",
+            width,
+            height,
+            depth,
+            maximum_pixel,
+            comment.len()
+        );
+        trace!("pixel data at offset {}, {} bytes", idx, bytes.len() - idx);
+
+        let image = PAM {
+            width,
+            height,
+            depth,
+            maximum_pixel,
+            tuple_type: tuple_type.unwrap_or(""),
+            comment,
+            pixel_data: &bytes[idx..],
+        };
+        Ok((image, idx))
+    }
+}
+
+/// Parses the decimal value of a PAM header line found at `pos`.
+fn parse_number(
+    value: &str,
+    pos: usize,
+    field: Field,
+    recovered: RecoveredHeader,
+) -> Result<usize, PNMError> {
+    let error = |offset: usize, ctx| ParseError {
+        pos: pos + offset,
+        got: value.as_bytes().get(offset).copied().unwrap_or(b'\n'),
+        ctx,
+        expected: Expected::Digit,
+        field,
+        recovered,
+    };
+    if let Some(offset) = value.bytes().position(|b| !b.is_ascii_digit()) {
+        return Err(error(offset, "expected digit."));
+    }
+    if value.is_empty() {
+        return Err(error(0, "expected digit."));
+    }
+    let number = value.parse().map_err(|_| error(0, "number too large."))?;
+    if field == Field::Depth && number == 0 {
+        return Err(error(0, "depth must not be zero."));
+    }
+    Ok(number)
+}
+
+impl PNMImage<'_> {
+    /// Returns the number of samples per pixel, e.g. 4 for `RGB_ALPHA` PAM images,
+    /// 3 for PPM images and 1 for PGM and PBM images.
+    pub fn depth(&self) -> usize {
+        self.channels()
+    }
+
+    /// Returns the tuple type of a PAM image, or the one implied by the format of other images
+    /// (`RGB`, `GRAYSCALE` or `BLACKANDWHITE`).
+    /// Returns an empty string for PAM images without a tuple type.
+    pub fn tuple_type(&self) -> &str {
+        self.header().tuple_type
+    }
+
+    /// Returns the raw bytes of all samples of the pixel at the specified (x, y) coordinate,
+    /// two bytes per sample for images with a maximum pixel value above 255.
+    /// Returns `None` if the pixel is outside the bounds of the image or the image is a bitmap.
+    pub fn pixel_samples(&self, x: usize, y: usize) -> Option<&[u8]> {
+        if self.is_bitmap() || x >= self.width() || y >= self.height() {
+            return None;
+        }
+        let pixel_size = self.pixel_size();
+        let idx = y
+            .checked_mul(self.width())
+            .and_then(|idx| idx.checked_add(x)?.checked_mul(pixel_size))?;
+        self.pixel_data().get(idx..idx.checked_add(pixel_size)?)
+    }
+
+    /// Returns the RGBA values of the pixel at the specified (x, y) coordinate.
+    /// Returns `None` if the pixel is outside the bounds of the image.
+    ///
+    /// Alpha is taken from the last sample of images whose tuple type ends in `_ALPHA`,
+    /// such as `RGB_ALPHA` and `GRAYSCALE_ALPHA`. Other images are opaque, with an alpha of 255.
+    /// All samples are scaled from `0..=maximum_pixel` to `0..=255`.
+    pub fn pixel_rgba(&self, x: usize, y: usize) -> Option<(u8, u8, u8, u8)> {
+        let (r, g, b) = self.pixel_rgb(x, y)?;
+        let alpha = if self.tuple_type().ends_with("_ALPHA") && self.depth() > 1 {
+            let samples = self.pixel_samples(x, y)?;
+            let alpha = crate::sample_value(&samples[samples.len() - self.sample_size()..]);
+            u8::from_sample(alpha, self.maximum_pixel().min(65535) as u16)
+        } else {
+            255
+        };
+        Some((r, g, b, alpha))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn pam() {
+        let raw_img = b"P7\n# icon\n# two lines\nWIDTH 2\nHEIGHT 1\nDEPTH 4\nMAXVAL 255\n# later\nTUPLTYPE RGB_ALPHA\nENDHDR\n\x01\x02\x03\x80\xff\x00\x00\xff";
+        let pam_img = PNMImage::from_parse(raw_img).unwrap();
+        assert_eq!(pam_img.comment(), "# icon\n# two lines");
+        assert_eq!(pam_img.depth(), 4);
+        assert_eq!(pam_img.tuple_type(), "RGB_ALPHA");
+        assert_eq!(pam_img.pixel_samples(1, 0), Some(&[0xff, 0, 0, 0xff][..]));
+        assert_eq!(pam_img.pixel_rgba(0, 0), Some((1, 2, 3, 0x80)));
+        assert_eq!(pam_img.pixel_rgb(1, 0), Some((0xff, 0, 0)));
+        assert_eq!(pam_img.pixel_rgba(2, 0), None);
+        assert_eq!(pam_img.validate(), Ok(()));
+
+        let gray = PNMImage::from_parse(
+            b"P7\nWIDTH 1\nHEIGHT 1\nDEPTH 2\nMAXVAL 65535\nTUPLTYPE GRAYSCALE_ALPHA\nENDHDR\n\x12\x34\x56\x78",
+        )
+        .unwrap();
+        assert_eq!(gray.pixel_rgba(0, 0), Some((0x12, 0x12, 0x12, 0x56)));
+        assert_eq!(gray.pixel_gray16(0, 0), Some(0x1234));
+
+        let ppm_img = PNMImage::from_parse(include_bytes!("./binary.ppm")).unwrap();
+        assert_eq!(ppm_img.depth(), 3);
+        assert_eq!(ppm_img.tuple_type(), "RGB");
+        assert_eq!(ppm_img.pixel_rgba(31, 7), Some((255, 0, 0, 255)));
+    }
+
+    #[test]
+    fn pam_errors() {
+        assert!(matches!(
+            PNMImage::from_parse(b"P7\nWIDTH 1\nHEIGHT 1\nMAXVAL 255\nENDHDR\n"),
+            Err(ParseError {
+                field: Field::Depth,
+                pos: 31,
+                ..
+            })
+        ));
+        assert!(matches!(
+            PNMImage::from_parse(b"P7\n WIDTH  1x\n"),
+            Err(ParseError {
+                field: Field::Width,
+                pos: 12,
+                got: b'x',
+                ..
+            })
+        ));
+        assert!(matches!(
+            PNMImage::from_parse(b"P7\nWIDTH 1\nDEPTH 0\n"),
+            Err(ParseError {
+                field: Field::Depth,
+                recovered: RecoveredHeader { width: Some(1), .. },
+                ..
+            })
+        ));
+        assert_eq!(
+            PNMImage::from_parse(b"P7\nWIDTH 1\n").unwrap_err(),
+            UnexpectedEOF
+        );
+        // a key without a value, from a buffer not known at compile time
+        let mut no_value = [0; 16];
+        no_value.copy_from_slice(b"P7\nWIDTH\nENDHDR\n");
+        assert!(matches!(
+            PNMImage::from_parse(&no_value),
+            Err(ParseError {
+                field: Field::Width,
+                pos: 8,
+                got: b'\n',
+                ..
+            })
+        ));
+        assert!(matches!(
+            PNMImage::from_parse(b"P7\n  HEIGHT   \n"),
+            Err(ParseError {
+                field: Field::Height,
+                pos: 11,
+                ..
+            })
+        ));
+
+        let huge = PNMImage::from_parse(
+            b"P7\nWIDTH 100000000000\nHEIGHT 100000000000\nDEPTH 3\nMAXVAL 255\nENDHDR\n",
+        )
+        .unwrap();
+        assert_eq!(huge.pixel_samples(5, 99999999999), None);
+    }
+}
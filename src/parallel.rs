@@ -0,0 +1,131 @@
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+#[cfg(feature = "rayon")]
+use crate::{BufferTooSmall, PNMImage, PixelFormat};
+
+/// Calls `f` with the index and bytes of every `chunk_len` chunk of `out`, which are the rows of
+/// a bulk operation. The chunks are processed in parallel if `parallel` is set and the `rayon`
+/// feature is enabled.
+pub(crate) fn for_each_row<F>(out: &mut [u8], chunk_len: usize, parallel: bool, f: F)
+where
+    F: Fn(usize, &mut [u8]) + Send + Sync,
+{
+    if chunk_len == 0 {
+        return;
+    }
+    #[cfg(feature = "rayon")]
+    if parallel {
+        out.par_chunks_mut(chunk_len)
+            .enumerate()
+            .for_each(|(y, row)| f(y, row));
+        return;
+    }
+    #[cfg(not(feature = "rayon"))]
+    let _ = parallel;
+    out.chunks_mut(chunk_len)
+        .enumerate()
+        .for_each(|(y, row)| f(y, row));
+}
+
+#[cfg(feature = "rayon")]
+impl PNMImage<'_> {
+    /// Like [`decode_into`](Self::decode_into), but decodes the rows in parallel.
+    pub fn par_decode_into(
+        &self,
+        format: PixelFormat,
+        out: &mut [u8],
+    ) -> Result<(), BufferTooSmall> {
+        self.decode_into_with(format, out, format.row_len(self.width()), true)
+    }
+
+    /// Like [`decode_into_strided`](Self::decode_into_strided), but decodes the rows in parallel.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `row_stride` is smaller than [`PixelFormat::row_len`] of the image width.
+    pub fn par_decode_into_strided(
+        &self,
+        format: PixelFormat,
+        out: &mut [u8],
+        row_stride: usize,
+    ) -> Result<(), BufferTooSmall> {
+        self.decode_into_with(format, out, row_stride, true)
+    }
+
+    /// Like [`thumbnail`](Self::thumbnail), but computes the rows of the thumbnail in parallel.
+    pub fn par_thumbnail(
+        &self,
+        max_w: usize,
+        max_h: usize,
+        out: &mut [u8],
+    ) -> Result<(usize, usize), BufferTooSmall> {
+        self.thumbnail_with(max_w, max_h, out, true)
+    }
+
+    /// Like [`upscale_integer`](Self::upscale_integer), but writes the rows in parallel.
+    pub fn par_upscale_integer(
+        &self,
+        factor: usize,
+        out: &mut [u8],
+    ) -> Result<(usize, usize), BufferTooSmall> {
+        self.upscale_integer_with(factor, out, true)
+    }
+
+    /// Like [`convolve3x3`](Self::convolve3x3), but filters the rows in parallel.
+    pub fn par_convolve3x3(
+        &self,
+        kernel: &[i16; 9],
+        divisor: i16,
+        out: &mut [u8],
+    ) -> Result<(), BufferTooSmall> {
+        self.convolve3x3_with(kernel, divisor, out, true)
+    }
+}
+
+#[cfg(all(test, feature = "rayon"))]
+mod test {
+    use super::*;
+    use crate::filter::SOBEL_X;
+
+    #[test]
+    fn parallel_matches_sequential() {
+        let raw_img = include_bytes!("./binary.ppm");
+        let ppm_img = PNMImage::from_parse(raw_img).unwrap();
+        let (mut seq, mut par) = ([0; 128 * 128 * 4], [0; 128 * 128 * 4]);
+
+        ppm_img
+            .decode_into(PixelFormat::Rgb565Le, &mut seq)
+            .unwrap();
+        ppm_img
+            .par_decode_into(PixelFormat::Rgb565Le, &mut par)
+            .unwrap();
+        assert_eq!(seq, par);
+        ppm_img
+            .decode_into_strided(PixelFormat::Rgba8888, &mut seq, 260)
+            .unwrap();
+        ppm_img
+            .par_decode_into_strided(PixelFormat::Rgba8888, &mut par, 260)
+            .unwrap();
+        assert_eq!(seq, par);
+        assert_eq!(
+            ppm_img.thumbnail(20, 20, &mut seq),
+            ppm_img.par_thumbnail(20, 20, &mut par)
+        );
+        assert_eq!(seq, par);
+        assert_eq!(
+            ppm_img.upscale_integer(2, &mut seq),
+            ppm_img.par_upscale_integer(2, &mut par)
+        );
+        assert_eq!(seq, par);
+        ppm_img.convolve3x3(&SOBEL_X, 1, &mut seq).unwrap();
+        ppm_img.par_convolve3x3(&SOBEL_X, 1, &mut par).unwrap();
+        assert_eq!(seq, par);
+        assert_eq!(
+            ppm_img.par_convolve3x3(&SOBEL_X, 1, &mut par[..10]),
+            Err(BufferTooSmall {
+                required: 64 * 64 * 3
+            })
+        );
+    }
+}
@@ -0,0 +1,98 @@
+use core::fmt;
+
+use crate::PNMImage;
+
+/// Characters used for increasing brightness, as seen on a dark terminal
+const RAMP: &[u8] = b" .:-=+*#%@";
+
+impl PNMImage<'_> {
+    /// Writes a preview of the image as ASCII art to `writer`, e.g. a serial console
+    ///
+    /// The image is downscaled to at most `max_cols` characters per line, averaging the brightness
+    /// of the pixels covered by each character. As characters are about twice as tall as they
+    /// are wide, each character covers twice as many rows as columns. Every line ends with `\n`.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_cols` - The maximum number of characters per line
+    /// * `writer` - The destination of the preview
+    pub fn preview_ascii(&self, max_cols: usize, mut writer: impl fmt::Write) -> fmt::Result {
+        let (width, height) = (self.width(), self.height());
+        let cols = width.min(max_cols);
+        if cols == 0 || height == 0 {
+            return Ok(());
+        }
+        let rows = (height * cols / width / 2).max(1);
+        let maximum_pixel = self.maximum_pixel().max(1) as u64;
+        for row in 0..rows {
+            let (y0, y1) = (row * height / rows, (row + 1) * height / rows);
+            for col in 0..cols {
+                let (x0, x1) = (col * width / cols, (col + 1) * width / cols);
+                let mut sum = 0u64;
+                for y in y0..y1 {
+                    for x in x0..x1 {
+                        sum += self.pixel_gray16(x, y).unwrap_or(0) as u64;
+                    }
+                }
+                let count = ((y1 - y0) * (x1 - x0)) as u64;
+                let level = (sum * (RAMP.len() as u64 - 1) / (count * maximum_pixel)) as usize;
+                writer.write_char(RAMP[level.min(RAMP.len() - 1)] as char)?;
+            }
+            writer.write_char('\n')?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Collects written text into a fixed-size buffer
+    struct Text {
+        buf: [u8; 256],
+        len: usize,
+    }
+
+    impl fmt::Write for Text {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            let dst = self
+                .buf
+                .get_mut(self.len..self.len + s.len())
+                .ok_or(fmt::Error)?;
+            dst.copy_from_slice(s.as_bytes());
+            self.len += s.len();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn preview() {
+        let raw_img = include_bytes!("./binary.ppm");
+        let ppm_img = PNMImage::from_parse(raw_img).unwrap();
+        let mut text = Text {
+            buf: [0; 256],
+            len: 0,
+        };
+        ppm_img.preview_ascii(16, &mut text).unwrap();
+
+        let lines = core::str::from_utf8(&text.buf[..text.len]).unwrap();
+        assert_eq!(lines.lines().count(), 8);
+        assert!(lines.lines().all(|line| line.len() == 16));
+        // red maps to a dim character, the white center to the brightest one
+        assert_eq!(lines.as_bytes()[7], b':');
+        assert_eq!(lines.lines().nth(3).unwrap().as_bytes()[7], b'@');
+    }
+
+    #[test]
+    fn preview_gray() {
+        let pgm_img =
+            PNMImage::from_parse(b"P5\n4 2\n15\n\x00\x05\x0a\x0f\x00\x05\x0a\x0f").unwrap();
+        let mut text = Text {
+            buf: [0; 256],
+            len: 0,
+        };
+        pgm_img.preview_ascii(80, &mut text).unwrap();
+        assert_eq!(&text.buf[..text.len], b" -*@\n");
+    }
+}
@@ -0,0 +1,154 @@
+use crate::PNMImage;
+
+/// Error type that represents the different quantization errors
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QuantizeError {
+    /// The output buffer is too small to hold a palette index per pixel
+    BufferTooSmall {
+        /// The number of bytes the output buffer needs to hold
+        required: usize,
+    },
+    /// The palette is empty or holds more than 256 colors
+    InvalidPalette,
+}
+
+/// 4x4 Bayer threshold matrix used for ordered dithering
+const BAYER_4X4: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+impl PNMImage<'_> {
+    /// Maps every pixel to the index of the nearest color in `palette`.
+    ///
+    /// One index is written per pixel into `out`, in row-major order.
+    /// The nearest color is the one with the smallest squared RGB distance; ties go to the lower index.
+    /// Samples are scaled from `0..=maximum_pixel` to `0..=255` before they are compared.
+    ///
+    /// # Arguments
+    ///
+    /// * `palette` - The colors to choose from, between 1 and 256
+    /// * `out` - A buffer of at least `width * height` bytes receiving the palette indices
+    pub fn quantize(&self, palette: &[(u8, u8, u8)], out: &mut [u8]) -> Result<(), QuantizeError> {
+        self.quantize_with(palette, false, out)
+    }
+
+    /// Like [`quantize`](Self::quantize), but applies 4x4 ordered dithering first.
+    ///
+    /// The dither spread is tuned for small palettes with widely spaced colors,
+    /// such as black/white/red e-paper or 8-color LED panels.
+    pub fn quantize_dithered(
+        &self,
+        palette: &[(u8, u8, u8)],
+        out: &mut [u8],
+    ) -> Result<(), QuantizeError> {
+        self.quantize_with(palette, true, out)
+    }
+
+    fn quantize_with(
+        &self,
+        palette: &[(u8, u8, u8)],
+        dither: bool,
+        out: &mut [u8],
+    ) -> Result<(), QuantizeError> {
+        if palette.is_empty() || palette.len() > 256 {
+            return Err(QuantizeError::InvalidPalette);
+        }
+        let width = self.width();
+        let required = width.saturating_mul(self.height());
+        if out.len() < required {
+            return Err(QuantizeError::BufferTooSmall { required });
+        }
+
+        for (i, ((mut r, mut g, mut b), idx)) in self.pixels::<u8>().zip(out.iter_mut()).enumerate()
+        {
+            if dither {
+                // spread the threshold over roughly one palette step in both directions
+                let t = BAYER_4X4[(i / width) & 3][(i % width) & 3] as i16;
+                let offset = (t * 2 - 15) * 255 / 32;
+                r = (r as i16 + offset).clamp(0, 255) as u8;
+                g = (g as i16 + offset).clamp(0, 255) as u8;
+                b = (b as i16 + offset).clamp(0, 255) as u8;
+            }
+            *idx = nearest(palette, (r, g, b));
+        }
+        Ok(())
+    }
+}
+
+/// Returns the index of the palette color closest to `color`.
+fn nearest(palette: &[(u8, u8, u8)], color: (u8, u8, u8)) -> u8 {
+    let distance = |&(r, g, b): &(u8, u8, u8)| {
+        let dr = r as i32 - color.0 as i32;
+        let dg = g as i32 - color.1 as i32;
+        let db = b as i32 - color.2 as i32;
+        dr * dr + dg * dg + db * db
+    };
+    let mut best = 0;
+    for (i, entry) in palette.iter().enumerate().skip(1) {
+        if distance(entry) < distance(&palette[best]) {
+            best = i;
+        }
+    }
+    best as u8
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EPAPER: [(u8, u8, u8); 3] = [(0, 0, 0), (255, 255, 255), (255, 0, 0)];
+
+    #[test]
+    fn quantize_to_palette() {
+        let raw_img = include_bytes!("./binary.ppm");
+        let ppm_img = PNMImage::from_parse(raw_img).unwrap();
+        let mut out = [0xff; 64 * 64];
+
+        ppm_img.quantize(&EPAPER, &mut out).unwrap();
+        assert_eq!(out[0], 0);
+        assert_eq!(out[7 * 64 + 31], 2);
+        assert_eq!(out[31 * 64 + 31], 1);
+        // pure green is closest to black
+        assert_eq!(out[7 * 64 + 7], 0);
+
+        // dithering leaves palette colors untouched
+        ppm_img.quantize_dithered(&EPAPER, &mut out).unwrap();
+        assert_eq!(out[0], 0);
+        assert_eq!(out[7 * 64 + 31], 2);
+        assert_eq!(out[31 * 64 + 31], 1);
+
+        assert_eq!(
+            ppm_img.quantize(&EPAPER, &mut out[..100]),
+            Err(QuantizeError::BufferTooSmall { required: 64 * 64 })
+        );
+        assert_eq!(
+            ppm_img.quantize(&[], &mut out),
+            Err(QuantizeError::InvalidPalette)
+        );
+        assert_eq!(
+            ppm_img.quantize_dithered(&[(0, 0, 0); 257], &mut out),
+            Err(QuantizeError::InvalidPalette)
+        );
+
+        // white bitmap pixels and full gray samples of any maximum pixel value are white
+        let pbm_img = PNMImage::from_parse(b"P4\n2 1\n\x40").unwrap();
+        pbm_img.quantize(&EPAPER, &mut out).unwrap();
+        assert_eq!(out[..2], [1, 0]);
+        let pgm_img = PNMImage::from_parse(b"P5\n2 1\n15\n\x0f\x02").unwrap();
+        pgm_img.quantize(&EPAPER, &mut out).unwrap();
+        assert_eq!(out[..2], [1, 0]);
+    }
+
+    #[test]
+    fn dithered_mid_gray() {
+        let mut raw_img = [127u8; 18 + 4 * 4 * 3];
+        raw_img[..18].copy_from_slice(b"P6\n# gray\n4 4\n255\n");
+        let gray = PNMImage::from_parse(&raw_img).unwrap();
+        let mut out = [0xff; 16];
+
+        gray.quantize(&EPAPER[..2], &mut out).unwrap();
+        assert!(out.iter().all(|&i| i == 0));
+
+        // half of the pixels should flip to white
+        gray.quantize_dithered(&EPAPER[..2], &mut out).unwrap();
+        assert_eq!(out.iter().filter(|&&i| i == 1).count(), 8);
+    }
+}
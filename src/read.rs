@@ -0,0 +1,59 @@
+use crate::PNMError;
+
+/// Error type for reading PNM images from an I/O source
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReadError<E> {
+    /// The underlying reader failed
+    Io(E),
+    /// The data read is not a valid PNM image
+    Parse(PNMError),
+    /// The buffer is too small to hold the header or the pixel data
+    BufferTooSmall {
+        /// The number of bytes the buffer needs to hold at least
+        required: usize,
+    },
+    /// The requested region does not lie within the image
+    OutOfBounds,
+    /// The header is longer than the reader accepts
+    HeaderTooLong {
+        /// The maximum length of the header in bytes
+        max: usize,
+    },
+}
+
+impl<E> From<PNMError> for ReadError<E> {
+    fn from(err: PNMError) -> Self {
+        ReadError::Parse(err)
+    }
+}
+
+#[cfg(any(feature = "async", feature = "embedded-io"))]
+impl<E> From<embedded_io::ReadExactError<E>> for ReadError<E> {
+    fn from(err: embedded_io::ReadExactError<E>) -> Self {
+        match err {
+            embedded_io::ReadExactError::UnexpectedEof => ReadError::Parse(PNMError::UnexpectedEOF),
+            embedded_io::ReadExactError::Other(err) => ReadError::Io(err),
+        }
+    }
+}
+
+/// Checks whether `header` holds a complete PNM header, which is assumed when the last byte read
+/// terminated the maximum pixel value.
+///
+/// Returns the number of pixel data bytes following the header once it is complete.
+#[cfg(any(
+    feature = "async",
+    feature = "embedded-io",
+    feature = "embedded-storage",
+    feature = "std"
+))]
+pub(crate) fn header_complete(header: &[u8]) -> Result<Option<usize>, PNMError> {
+    if header.last() != Some(&b'\n') {
+        return Ok(None);
+    }
+    match crate::PNMImage::parse(header) {
+        Ok((image, _)) => image.raster_len().map(Some).ok_or(PNMError::UnexpectedEOF),
+        Err(PNMError::UnexpectedEOF) => Ok(None),
+        Err(err) => Err(err),
+    }
+}
@@ -0,0 +1,205 @@
+use embedded_io_async::Read;
+
+use crate::{PNMHeader, PNMImage, ReadError};
+
+/// Reads the header from `reader` into `buf` one byte at a time, so no pixel data is consumed.
+/// Returns the length of the header and the number of pixel data bytes following it.
+async fn read_header<R: Read>(
+    reader: &mut R,
+    buf: &mut [u8],
+) -> Result<(usize, usize), ReadError<R::Error>> {
+    let mut len = 0;
+    loop {
+        let byte = buf
+            .get_mut(len..len + 1)
+            .ok_or(ReadError::BufferTooSmall { required: len + 1 })?;
+        reader.read_exact(byte).await?;
+        len += 1;
+        if let Some(raster_len) = crate::read::header_complete(&buf[..len])? {
+            return Ok((len, raster_len));
+        }
+    }
+}
+
+impl<'a> PNMImage<'a> {
+    /// Reads a PNM image from an async reader into `buf`
+    ///
+    /// The header is read first, followed by exactly as much pixel data as the header describes,
+    /// so `reader` is left positioned right after the image.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The source to read from, e.g. an SPI flash driver or a network socket
+    /// * `buf` - A buffer large enough to hold the header and pixel data
+    ///
+    /// # Returns
+    ///
+    /// A Result object containing the PNMImage borrowing `buf` if successful, otherwise a ReadError
+    pub async fn read_async<R: Read>(
+        mut reader: R,
+        buf: &'a mut [u8],
+    ) -> Result<Self, ReadError<R::Error>> {
+        let (header_len, raster_len) = read_header(&mut reader, buf).await?;
+        let required = header_len + raster_len;
+        let pixel_data = buf
+            .get_mut(header_len..required)
+            .ok_or(ReadError::BufferTooSmall { required })?;
+        reader.read_exact(pixel_data).await?;
+
+        let buf: &'a [u8] = buf;
+        Ok(Self::from_parse(&buf[..required])?)
+    }
+
+    /// Reads the header of a PNM image from an async reader and returns a reader for its rows
+    ///
+    /// Only the header is stored in `scratch`; rows are read on demand with
+    /// [`AsyncRowReader::next_row`], so the image never needs to fit in memory.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The source to read from
+    /// * `scratch` - A buffer large enough to hold the header
+    ///
+    /// # Returns
+    ///
+    /// A Result object containing the AsyncRowReader if successful, otherwise a ReadError
+    pub async fn read_rows_async<R: Read>(
+        mut reader: R,
+        scratch: &'a mut [u8],
+    ) -> Result<AsyncRowReader<'a, R>, ReadError<R::Error>> {
+        let (header_len, _) = read_header(&mut reader, scratch).await?;
+        let scratch: &'a [u8] = scratch;
+        let image = Self::from_parse(&scratch[..header_len])?;
+        Ok(AsyncRowReader {
+            reader,
+            image,
+            next_row: 0,
+        })
+    }
+}
+
+/// Reads the rows of a PNM image one by one from an async reader, created by [`PNMImage::read_rows_async`]
+#[derive(Debug)]
+pub struct AsyncRowReader<'a, R> {
+    reader: R,
+    image: PNMImage<'a>,
+    next_row: usize,
+}
+
+impl<'a, R: Read> AsyncRowReader<'a, R> {
+    /// Returns the header of the image.
+    pub fn header(&self) -> PNMHeader<'a> {
+        self.image.header()
+    }
+
+    /// Returns the width of the image.
+    pub fn width(&self) -> usize {
+        self.image.width()
+    }
+
+    /// Returns the height of the image.
+    pub fn height(&self) -> usize {
+        self.image.height()
+    }
+
+    /// Returns the maximum pixel value of the image.
+    pub fn maximum_pixel(&self) -> usize {
+        self.image.maximum_pixel()
+    }
+
+    /// Returns the comment associated with the image.
+    pub fn comment(&self) -> &'a str {
+        self.image.header().comment
+    }
+
+    /// Returns the number of bytes of a single row of pixel data.
+    pub fn row_len(&self) -> usize {
+        // the header was checked to describe a raster whose size fits in a usize
+        self.image.row_len().unwrap_or_default()
+    }
+
+    /// Reads the next row of pixel data into `row`.
+    ///
+    /// Returns the index of the row read, or `None` once all rows have been read.
+    pub async fn next_row(&mut self, row: &mut [u8]) -> Result<Option<usize>, ReadError<R::Error>> {
+        if self.next_row >= self.height() {
+            return Ok(None);
+        }
+        let required = self.row_len();
+        let row = row
+            .get_mut(..required)
+            .ok_or(ReadError::BufferTooSmall { required })?;
+        self.reader.read_exact(row).await?;
+        self.next_row += 1;
+        Ok(Some(self.next_row - 1))
+    }
+
+    /// Returns the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::future::Future;
+    use core::pin::pin;
+    use core::task::{Context, Poll, Waker};
+
+    use super::*;
+    use crate::PNMError;
+
+    fn block_on<F: Future>(fut: F) -> F::Output {
+        let mut fut = pin!(fut);
+        let mut cx = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(out) = fut.as_mut().poll(&mut cx) {
+                return out;
+            }
+        }
+    }
+
+    #[test]
+    fn read_async() {
+        let raw_img = include_bytes!("./binary.ppm");
+        let mut buf = [0; 64 * 64 * 3 + 64];
+        let ppm_img = block_on(PNMImage::read_async(&raw_img[..], &mut buf)).unwrap();
+
+        assert_eq!(
+            ppm_img.comment(),
+            "# Created by GIMP version 2.10.34 PNM plug-in"
+        );
+        assert_eq!(ppm_img.width(), 64);
+        assert_eq!(ppm_img.pixel_rgb(31, 7), Some((255, 0, 0)));
+
+        let mut small = [0; 64];
+        assert!(matches!(
+            block_on(PNMImage::read_async(&raw_img[..], &mut small)),
+            Err(ReadError::BufferTooSmall { required }) if required == raw_img.len()
+        ));
+        assert!(matches!(
+            block_on(PNMImage::read_async(&raw_img[..100], &mut buf)),
+            Err(ReadError::Parse(PNMError::UnexpectedEOF))
+        ));
+    }
+
+    #[test]
+    fn read_rows_async() {
+        let raw_img = include_bytes!("./binary.ppm");
+        let mut scratch = [0; 64];
+        let mut rows = block_on(PNMImage::read_rows_async(&raw_img[..], &mut scratch)).unwrap();
+        assert_eq!(rows.height(), 64);
+        assert_eq!(rows.row_len(), 64 * 3);
+
+        let mut row = [0; 64 * 3];
+        let mut count = 0;
+        while let Some(y) = block_on(rows.next_row(&mut row)).unwrap() {
+            if y == 31 {
+                assert_eq!(row[31 * 3..32 * 3], [255, 255, 255]);
+            }
+            count += 1;
+        }
+        assert_eq!(count, 64);
+        assert!(rows.into_inner().is_empty());
+    }
+}
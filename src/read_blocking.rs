@@ -0,0 +1,190 @@
+use embedded_io::{Read, Seek, SeekFrom};
+
+use crate::{PNMError, PNMImage, ReadError};
+
+/// Reads the header from `reader` into `buf` one byte at a time, so no pixel data is consumed.
+/// Returns the length of the header and the number of pixel data bytes following it.
+fn read_header<R: Read>(
+    reader: &mut R,
+    buf: &mut [u8],
+) -> Result<(usize, usize), ReadError<R::Error>> {
+    let mut len = 0;
+    loop {
+        let byte = buf
+            .get_mut(len..len + 1)
+            .ok_or(ReadError::BufferTooSmall { required: len + 1 })?;
+        reader.read_exact(byte)?;
+        len += 1;
+        if let Some(raster_len) = crate::read::header_complete(&buf[..len])? {
+            return Ok((len, raster_len));
+        }
+    }
+}
+
+impl<'a> PNMImage<'a> {
+    /// Reads a PNM image from a blocking reader into `scratch`
+    ///
+    /// The header is read one byte at a time, followed by exactly as much pixel data as the header
+    /// describes, so `reader` is left positioned right after the image.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The source to read from, e.g. an SD card driver
+    /// * `scratch` - A buffer large enough to hold the header and pixel data
+    ///
+    /// # Returns
+    ///
+    /// A Result object containing the PNMImage borrowing `scratch` if successful, otherwise a ReadError
+    pub fn read_from<R: Read>(
+        mut reader: R,
+        scratch: &'a mut [u8],
+    ) -> Result<Self, ReadError<R::Error>> {
+        let (header_len, raster_len) = read_header(&mut reader, scratch)?;
+
+        let required = header_len + raster_len;
+        let pixel_data = scratch
+            .get_mut(header_len..required)
+            .ok_or(ReadError::BufferTooSmall { required })?;
+        reader.read_exact(pixel_data)?;
+
+        let scratch: &'a [u8] = scratch;
+        Ok(Self::from_parse(&scratch[..required])?)
+    }
+
+    /// Reads only the pixels of a rectangular region of a PNM image from a seekable reader into `out`
+    ///
+    /// The reader must be positioned at the start of the image. After parsing the header, each row
+    /// of the region is read by seeking directly to it, so images larger than the available memory
+    /// can be partially redrawn. The pixel data written to `out` is laid out like that of a
+    /// `width` x `height` image. Bitmaps (P4) are not supported.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The source to read from
+    /// * `x` - The x coordinate of the top left corner of the region
+    /// * `y` - The y coordinate of the top left corner of the region
+    /// * `width` - The width of the region
+    /// * `height` - The height of the region
+    /// * `out` - A buffer receiving the pixel data of the region, which also needs to hold the header temporarily
+    pub fn decode_region<R: Read + Seek>(
+        mut reader: R,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+        out: &mut [u8],
+    ) -> Result<(), ReadError<R::Error>> {
+        let start = reader.stream_position().map_err(ReadError::Io)?;
+        let (header_len, _) = read_header(&mut reader, out)?;
+        let header = PNMImage::from_parse(&out[..header_len])?;
+        if header.is_bitmap() {
+            // pixels of bitmaps do not start at byte boundaries
+            return Err(ReadError::Parse(PNMError::UnsupportedPNMFormat));
+        }
+        let (image_width, pixel_size) = (header.width(), header.pixel_size());
+
+        let fits_x = x.checked_add(width).is_some_and(|end| end <= image_width);
+        let fits_y = y
+            .checked_add(height)
+            .is_some_and(|end| end <= header.height());
+        if !fits_x || !fits_y {
+            return Err(ReadError::OutOfBounds);
+        }
+        let row_len = width * pixel_size;
+        let required = row_len * height;
+        if out.len() < required {
+            return Err(ReadError::BufferTooSmall { required });
+        }
+
+        for (row, dst) in out[..required].chunks_exact_mut(row_len).enumerate() {
+            let offset = ((y + row) * image_width + x) * pixel_size;
+            let pos = start + (header_len + offset) as u64;
+            reader.seek(SeekFrom::Start(pos)).map_err(ReadError::Io)?;
+            reader.read_exact(dst)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::convert::Infallible;
+
+    use super::*;
+
+    /// A seekable reader over a byte slice
+    struct Cursor<'a> {
+        data: &'a [u8],
+        pos: usize,
+    }
+
+    impl embedded_io::ErrorType for Cursor<'_> {
+        type Error = Infallible;
+    }
+
+    impl Read for Cursor<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize, Infallible> {
+            let mut rest = &self.data[self.pos.min(self.data.len())..];
+            let len = rest.read(buf)?;
+            self.pos += len;
+            Ok(len)
+        }
+    }
+
+    impl Seek for Cursor<'_> {
+        fn seek(&mut self, pos: SeekFrom) -> Result<u64, Infallible> {
+            self.pos = match pos {
+                SeekFrom::Start(pos) => pos as usize,
+                SeekFrom::End(offset) => (self.data.len() as i64 + offset) as usize,
+                SeekFrom::Current(offset) => (self.pos as i64 + offset) as usize,
+            };
+            Ok(self.pos as u64)
+        }
+    }
+
+    #[test]
+    fn read_from() {
+        let raw_img = include_bytes!("./binary.ppm");
+        let mut reader = &raw_img[..];
+        let mut scratch = [0; 64 * 64 * 3 + 64];
+        let ppm_img = PNMImage::read_from(&mut reader, &mut scratch).unwrap();
+
+        assert_eq!(ppm_img.width(), 64);
+        assert_eq!(ppm_img.pixel_rgb(56, 56), Some((0, 0, 255)));
+        assert!(reader.is_empty());
+
+        let mut small = [0; 32];
+        assert_eq!(
+            PNMImage::read_from(&raw_img[..], &mut small),
+            Err(ReadError::BufferTooSmall { required: 33 })
+        );
+        assert_eq!(
+            PNMImage::read_from(&b"P3\n1 1\n255\n"[..], &mut scratch),
+            Err(ReadError::Parse(PNMError::UnsupportedPNMFormat))
+        );
+    }
+
+    #[test]
+    fn decode_region() {
+        let raw_img = include_bytes!("./binary.ppm");
+        let mut reader = Cursor {
+            data: raw_img,
+            pos: 0,
+        };
+        let mut out = [0; 64];
+        PNMImage::decode_region(&mut reader, 30, 6, 3, 2, &mut out).unwrap();
+        assert_eq!(out[..3], [255, 0, 0]);
+        assert_eq!(out[15..18], [255, 0, 0]);
+
+        reader.pos = 0;
+        assert_eq!(
+            PNMImage::decode_region(&mut reader, 62, 0, 3, 1, &mut out),
+            Err(ReadError::OutOfBounds)
+        );
+        reader.pos = 0;
+        assert_eq!(
+            PNMImage::decode_region(&mut reader, 0, 0, 32, 1, &mut out),
+            Err(ReadError::BufferTooSmall { required: 96 })
+        );
+    }
+}
@@ -0,0 +1,215 @@
+use core::ops::Range;
+use std::io::{self, BufRead};
+
+use crate::{PNMHeader, PNMImage, ReadError};
+
+/// The maximum length of a header read by [`PNMImage::read_header`], which bounds the memory
+/// and time spent on streams that never complete a header
+const MAX_HEADER_LEN: usize = 4096;
+
+impl From<io::Error> for ReadError<io::Error> {
+    fn from(err: io::Error) -> Self {
+        if err.kind() == io::ErrorKind::UnexpectedEof {
+            ReadError::Parse(crate::PNMError::UnexpectedEOF)
+        } else {
+            ReadError::Io(err)
+        }
+    }
+}
+
+impl PNMImage<'_> {
+    /// Reads the header of a PNM image from a buffered reader and returns a reader for its rows
+    ///
+    /// No pixel data is consumed while reading the header, and rows are read on demand with
+    /// [`RowReader::next_row`], so streams such as stdin never need to be read in full. Once all
+    /// rows have been read, the reader is positioned right after the image, so the next image
+    /// of a stream of concatenated images can be read from [`RowReader::into_inner`].
+    /// Headers longer than 4096 bytes, e.g. due to long comments, are rejected.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The source to read from, e.g. a locked stdin
+    ///
+    /// # Returns
+    ///
+    /// A Result object containing the RowReader if successful, otherwise a ReadError
+    pub fn read_header<R: BufRead>(mut reader: R) -> Result<RowReader<R>, ReadError<io::Error>> {
+        let mut header = Vec::new();
+        loop {
+            if header.len() == MAX_HEADER_LEN {
+                return Err(ReadError::HeaderTooLong {
+                    max: MAX_HEADER_LEN,
+                });
+            }
+            let byte = *reader
+                .fill_buf()?
+                .first()
+                .ok_or(io::Error::from(io::ErrorKind::UnexpectedEof))?;
+            reader.consume(1);
+            header.push(byte);
+            if crate::read::header_complete(&header)?.is_some() {
+                break;
+            }
+        }
+        let image = PNMImage::from_parse(&header)?;
+
+        // the strings of the header borrow from the buffer, so their positions follow from their addresses
+        let range = |s: &str| match s.len() {
+            0 => 0..0,
+            len => {
+                let start = s.as_ptr() as usize - header.as_ptr() as usize;
+                start..start + len
+            }
+        };
+        let tuple_type = match image {
+            PNMImage::PAM { tuple_type, .. } => range(tuple_type),
+            _ => 0..0,
+        };
+        let parsed = image.header();
+        let comment = range(parsed.comment);
+        let row_len = image.row_len().unwrap_or_default();
+        let parsed = PNMHeader {
+            tuple_type: "",
+            comment: "",
+            ..parsed
+        };
+        Ok(RowReader {
+            reader,
+            buffer: header,
+            header: parsed,
+            tuple_type,
+            comment,
+            row_len,
+            next_row: 0,
+        })
+    }
+}
+
+/// Reads the rows of a PNM image one by one from a buffered reader, created by [`PNMImage::read_header`]
+#[derive(Debug)]
+pub struct RowReader<R> {
+    reader: R,
+    buffer: Vec<u8>,
+    header: PNMHeader<'static>,
+    /// The positions of the tuple type and comment within the buffer
+    tuple_type: Range<usize>,
+    comment: Range<usize>,
+    row_len: usize,
+    next_row: usize,
+}
+
+impl<R: BufRead> RowReader<R> {
+    /// Returns the header of the image.
+    pub fn header(&self) -> PNMHeader<'_> {
+        // both strings were checked to be valid UTF-8 when the header was read
+        let str_at = |range: &Range<usize>| {
+            core::str::from_utf8(&self.buffer[range.clone()]).unwrap_or_default()
+        };
+        let header = PNMHeader {
+            tuple_type: str_at(&self.tuple_type),
+            comment: str_at(&self.comment),
+            ..self.header
+        };
+        // fills in the tuple type implied by the format of PPM, PGM and PBM images
+        PNMImage::from_raw_parts_unchecked(header, &[]).header()
+    }
+
+    /// Returns the width of the image.
+    pub fn width(&self) -> usize {
+        self.header.width
+    }
+
+    /// Returns the height of the image.
+    pub fn height(&self) -> usize {
+        self.header.height
+    }
+
+    /// Returns the number of bytes of a single row of pixel data.
+    pub fn row_len(&self) -> usize {
+        self.row_len
+    }
+
+    /// Reads the next row of pixel data into `row`.
+    ///
+    /// Returns the index of the row read, or `None` once all rows have been read.
+    pub fn next_row(&mut self, row: &mut [u8]) -> Result<Option<usize>, ReadError<io::Error>> {
+        if self.next_row >= self.header.height {
+            return Ok(None);
+        }
+        let required = self.row_len;
+        let row = row
+            .get_mut(..required)
+            .ok_or(ReadError::BufferTooSmall { required })?;
+        self.reader.read_exact(row)?;
+        self.next_row += 1;
+        Ok(Some(self.next_row - 1))
+    }
+
+    /// Returns the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn read_header() {
+        let raw_img = include_bytes!("./binary.ppm");
+        let mut rows = PNMImage::read_header(&raw_img[..]).unwrap();
+        assert_eq!(
+            rows.header().comment,
+            "# Created by GIMP version 2.10.34 PNM plug-in"
+        );
+        assert_eq!(
+            (rows.width(), rows.height(), rows.row_len()),
+            (64, 64, 64 * 3)
+        );
+
+        let mut row = [0; 64 * 3];
+        let mut count = 0;
+        while let Some(y) = rows.next_row(&mut row).unwrap() {
+            if y == 31 {
+                assert_eq!(row[31 * 3..32 * 3], [255, 255, 255]);
+            }
+            count += 1;
+        }
+        assert_eq!(count, 64);
+        assert!(rows.into_inner().is_empty());
+
+        let frames = b"P5\n2 1\n255\n\x01\x02P5\n1 1\n255\n\x03";
+        let mut rows = PNMImage::read_header(&frames[..]).unwrap();
+        assert_eq!(rows.next_row(&mut row).unwrap(), Some(0));
+        assert_eq!(row[..2], [1, 2]);
+        let mut rows = PNMImage::read_header(rows.into_inner()).unwrap();
+        assert_eq!(rows.next_row(&mut row).unwrap(), Some(0));
+        assert_eq!(row[0], 3);
+
+        let mut rows = PNMImage::read_header(&raw_img[..100]).unwrap();
+        assert!(matches!(
+            rows.next_row(&mut row),
+            Err(ReadError::Parse(crate::PNMError::UnexpectedEOF))
+        ));
+        assert!(matches!(
+            PNMImage::read_header(&raw_img[..10]),
+            Err(ReadError::Parse(crate::PNMError::UnexpectedEOF))
+        ));
+
+        let rows = PNMImage::read_header(
+            &b"P7\nWIDTH 1\nHEIGHT 1\nDEPTH 1\nMAXVAL 255\nTUPLTYPE GRAYSCALE\nENDHDR\n"[..],
+        )
+        .unwrap();
+        assert_eq!(rows.header().tuple_type, "GRAYSCALE");
+        let rows = PNMImage::read_header(&raw_img[..]).unwrap();
+        assert_eq!(rows.header().tuple_type, "RGB");
+
+        let mut endless = b"P6\n# ".to_vec();
+        endless.resize(10_000, b'#');
+        assert!(matches!(
+            PNMImage::read_header(&endless[..]),
+            Err(ReadError::HeaderTooLong { max: 4096 })
+        ));
+    }
+}
@@ -0,0 +1,236 @@
+use core::ops::{BitOr, BitOrAssign};
+
+use crate::PNMError::{self, *};
+use crate::{Field, PNMImage};
+
+/// The defects fixed by [`PNMImage::from_parse_repair`], combined as bit flags
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Repairs(u8);
+
+impl Repairs {
+    /// Nothing was repaired.
+    pub const NONE: Repairs = Repairs(0);
+    /// The last header field was not followed by a newline, but by other whitespace or the pixel data.
+    pub const HEADER_NEWLINE: Repairs = Repairs(1 << 0);
+    /// The pixel data was short and padded with black pixels.
+    pub const PADDED: Repairs = Repairs(1 << 1);
+    /// The pixel data was short and the height was reduced to the number of complete rows.
+    pub const SHRUNK: Repairs = Repairs(1 << 2);
+    /// Samples above the maximum pixel value were clamped to it.
+    pub const CLAMPED: Repairs = Repairs(1 << 3);
+
+    /// Returns the raw bits of the flags.
+    pub fn bits(self) -> u8 {
+        self.0
+    }
+
+    /// Returns whether all flags of `other` are set.
+    pub fn contains(self, other: Repairs) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns whether no flags are set.
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl BitOr for Repairs {
+    type Output = Repairs;
+
+    fn bitor(self, rhs: Repairs) -> Repairs {
+        Repairs(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for Repairs {
+    fn bitor_assign(&mut self, rhs: Repairs) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// How [`PNMImage::from_parse_repair`] handles pixel data shorter than described by the header
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ShortData {
+    /// Pads the missing pixels with black.
+    #[default]
+    Pad,
+    /// Reduces the height of the image to the number of complete rows.
+    Shrink,
+}
+
+impl<'a> PNMImage<'a> {
+    /// Parses a binary PNM image (P7, P6, P5 or P4) like [`from_parse`](Self::from_parse),
+    /// repairing common defects instead of failing
+    ///
+    /// The following defects are repaired, which is reported by the returned [`Repairs`]:
+    ///
+    /// * A last header field that is followed by other whitespace, such as `\r\n`, or directly by
+    ///   the pixel data. If the first byte of the pixel data is a digit, it cannot be told apart
+    ///   from the header.
+    /// * Pixel data shorter than described by the header, handled as given by `short`
+    /// * Samples above the maximum pixel value, which are clamped
+    ///
+    /// The pixel data is copied into `scratch`, along with the header if it needs to be repaired.
+    /// If `scratch` is too small, the error reports the required size so the call can be retried.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - A byte array containing the PNM image data
+    /// * `scratch` - A buffer receiving the repaired pixel data
+    /// * `short` - How to handle short pixel data
+    ///
+    /// # Returns
+    ///
+    /// A Result object containing the parsed PNMImage and the repairs made if successful, otherwise a PNMError
+    pub fn from_parse_repair(
+        bytes: &'a [u8],
+        scratch: &'a mut [u8],
+        short: ShortData,
+    ) -> Result<(Self, Repairs), PNMError> {
+        let mut repairs = Repairs::NONE;
+        let bitmap = bytes.get(1) == Some(&b'4');
+        let (mut image, offset, header_len, pixels) = match Self::parse(bytes) {
+            Ok((image, offset)) => (image, offset, 0, scratch),
+            Err(ParseError {
+                pos, got, field, ..
+            }) if (field == Field::MaximumPixel || (bitmap && field == Field::Height))
+                && !got.is_ascii_digit()
+                && bytes[pos - 1].is_ascii_digit() =>
+            {
+                // parse a copy of the header that ends in a newline
+                let header_len = pos + 1;
+                if scratch.len() < header_len {
+                    return Err(ScratchTooSmall {
+                        required: header_len,
+                    });
+                }
+                let (header, pixels) = scratch.split_at_mut(header_len);
+                header[..pos].copy_from_slice(&bytes[..pos]);
+                header[pos] = b'\n';
+                let header: &'a [u8] = header;
+                let (image, _) = Self::parse(header)?;
+                let offset = match got {
+                    b'\r' if bytes.get(pos + 1) == Some(&b'\n') => pos + 2,
+                    b' ' | b'\t' | b'\r' => pos + 1,
+                    _ => pos,
+                };
+                repairs |= Repairs::HEADER_NEWLINE;
+                (image, offset, header_len, pixels)
+            }
+            Err(err) => return Err(err),
+        };
+
+        let data = bytes.get(offset..).unwrap_or_default();
+        let mut len = image.raster_len().ok_or(UnexpectedEOF)?;
+        if data.len() < len {
+            match short {
+                ShortData::Pad => repairs |= Repairs::PADDED,
+                ShortData::Shrink => {
+                    let row_len = image.row_len().unwrap_or_default().max(1);
+                    let rows = data.len() / row_len;
+                    let (PNMImage::PPMBinary { height, .. }
+                    | PNMImage::PGMBinary { height, .. }
+                    | PNMImage::PBMBinary { height, .. }
+                    | PNMImage::PAM { height, .. }) = &mut image;
+                    *height = rows;
+                    len = rows * row_len;
+                    repairs |= Repairs::SHRUNK;
+                }
+            }
+        }
+        if pixels.len() < len {
+            return Err(ScratchTooSmall {
+                required: header_len + len,
+            });
+        }
+
+        let pixels = &mut pixels[..len];
+        let copied = data.len().min(len);
+        pixels[..copied].copy_from_slice(&data[..copied]);
+        // a set bit is black in bitmaps
+        pixels[copied..].fill(if image.is_bitmap() { 0xff } else { 0 });
+
+        let maximum_pixel = image.maximum_pixel();
+        if !image.is_bitmap() && maximum_pixel != 255 && maximum_pixel != 65535 {
+            for sample in pixels.chunks_exact_mut(image.sample_size()) {
+                if let [high, low] = sample {
+                    if u16::from_be_bytes([*high, *low]) as usize > maximum_pixel {
+                        [*high, *low] = (maximum_pixel as u16).to_be_bytes();
+                        repairs |= Repairs::CLAMPED;
+                    }
+                } else if sample[0] as usize > maximum_pixel {
+                    sample[0] = maximum_pixel as u8;
+                    repairs |= Repairs::CLAMPED;
+                }
+            }
+        }
+        let pixels: &'a [u8] = pixels;
+        image.set_pixel_data(pixels);
+        Ok((image, repairs))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn repair_header() {
+        let mut scratch = [0; 32];
+        let (image, repairs) =
+            PNMImage::from_parse_repair(b"P6\n1 1\n255\x01\x02\x03", &mut scratch, ShortData::Pad)
+                .unwrap();
+        assert_eq!(repairs, Repairs::HEADER_NEWLINE);
+        assert_eq!(image.pixel_rgb(0, 0), Some((1, 2, 3)));
+
+        let mut scratch = [0; 32];
+        let (image, repairs) =
+            PNMImage::from_parse_repair(b"P4\n# crlf\n8 1\r\n\x0f", &mut scratch, ShortData::Pad)
+                .unwrap();
+        assert_eq!(repairs, Repairs::HEADER_NEWLINE);
+        assert_eq!(image.comment(), "# crlf");
+        assert_eq!(image.pixel_bit(4, 0), Some(true));
+
+        let raw_img = include_bytes!("./binary.ppm");
+        let mut scratch = [0; 64 * 64 * 3];
+        let (image, repairs) =
+            PNMImage::from_parse_repair(raw_img, &mut scratch, ShortData::Pad).unwrap();
+        assert!(repairs.is_empty());
+        assert!(image.eq_with_comment(&PNMImage::from_parse(raw_img).unwrap()));
+        assert!(matches!(
+            PNMImage::from_parse_repair(raw_img, &mut scratch[..100], ShortData::Pad),
+            Err(ScratchTooSmall { required }) if required == 64 * 64 * 3
+        ));
+        assert!(matches!(
+            PNMImage::from_parse_repair(b"P6\n1 x\n255\n", &mut scratch, ShortData::Pad),
+            Err(ParseError {
+                field: Field::Height,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn repair_pixels() {
+        let mut scratch = [0xaa; 8];
+        let raw_img = b"P5\n2 2\n100\n\x50\xff\x10";
+        let (image, repairs) =
+            PNMImage::from_parse_repair(raw_img, &mut scratch, ShortData::Pad).unwrap();
+        assert_eq!(repairs, Repairs::PADDED | Repairs::CLAMPED);
+        assert!(repairs.contains(Repairs::CLAMPED));
+        assert_eq!(image.pixel_data(), [0x50, 100, 0x10, 0]);
+
+        let (image, repairs) =
+            PNMImage::from_parse_repair(raw_img, &mut scratch, ShortData::Shrink).unwrap();
+        assert_eq!(repairs, Repairs::SHRUNK | Repairs::CLAMPED);
+        assert_eq!(image.height(), 1);
+        assert_eq!(image.pixel_data(), [0x50, 100]);
+
+        let mut scratch = [0; 2];
+        let (image, repairs) =
+            PNMImage::from_parse_repair(b"P4\n8 2\n\xaa", &mut scratch, ShortData::Pad).unwrap();
+        assert_eq!(repairs.bits(), Repairs::PADDED.bits());
+        assert_eq!(image.pixel_data(), [0xaa, 0xff]);
+    }
+}
@@ -0,0 +1,97 @@
+use crate::PNMError::{self, *};
+use crate::PNMImage;
+
+impl PNMImage<'_> {
+    /// Decodes a binary PNM image row by row, invoking `f` with the index and pixel data of each row
+    ///
+    /// Rows are handed out as soon as they are complete, so a display without a framebuffer can
+    /// be fed directly. If the pixel data is truncated, all complete rows are delivered before
+    /// the error is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - A byte array containing the PNM image data
+    /// * `f` - A callback receiving the row index and the raw bytes of the row
+    pub fn decode_rows<F>(bytes: &[u8], mut f: F) -> Result<(), PNMError>
+    where
+        F: FnMut(usize, &[u8]),
+    {
+        let image = PNMImage::from_parse(bytes)?;
+        let row_len = image.row_len().ok_or(UnexpectedEOF)?;
+        if row_len == 0 {
+            return Ok(());
+        }
+        let mut rows = image.pixel_data().chunks_exact(row_len);
+        for y in 0..image.height() {
+            f(y, rows.next().ok_or(UnexpectedEOF)?);
+        }
+        Ok(())
+    }
+
+    /// Decodes a plain (ASCII) PPM, PGM or PBM image (P3, P2 or P1) row by row, invoking `f` with the index and pixel data of each row
+    ///
+    /// Each row is decoded into `row`, which only needs to hold a single row of binary pixel data,
+    /// so the whole image never needs to fit in memory.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - A byte array containing the P3, P2 or P1 image data
+    /// * `row` - A buffer receiving the decoded pixel data of a single row
+    /// * `f` - A callback receiving the row index and the decoded bytes of the row
+    pub fn decode_rows_ascii<F>(bytes: &[u8], row: &mut [u8], mut f: F) -> Result<(), PNMError>
+    where
+        F: FnMut(usize, &[u8]),
+    {
+        let (image, mut idx) = PNMImage::parse_as(bytes, true)?;
+        let required = image.row_len().ok_or(UnexpectedEOF)?;
+        let row = row
+            .get_mut(..required)
+            .ok_or(ScratchTooSmall { required })?;
+        for y in 0..image.height() {
+            idx = crate::ascii::decode_row(bytes, idx, &image, row)?;
+            f(y, row);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn decode_rows() {
+        let raw_img = include_bytes!("./binary.ppm");
+        let mut count = 0;
+        PNMImage::decode_rows(raw_img, |y, row| {
+            assert_eq!(y, count);
+            assert_eq!(row.len(), 64 * 3);
+            if y == 31 {
+                assert_eq!(row[31 * 3..32 * 3], [255, 255, 255]);
+            }
+            count += 1;
+        })
+        .unwrap();
+        assert_eq!(count, 64);
+
+        let mut count = 0;
+        let result = PNMImage::decode_rows(&raw_img[..raw_img.len() - 1], |_, _| count += 1);
+        assert!(matches!(result, Err(UnexpectedEOF)));
+        assert_eq!(count, 63);
+    }
+
+    #[test]
+    fn decode_rows_ascii() {
+        let raw_img = b"P3\n2 2\n255\n1 2 3 4 5 6\n7 8 9 10 11 12\n";
+        let mut row = [0; 6];
+        let mut rows = [[0; 6]; 2];
+        PNMImage::decode_rows_ascii(raw_img, &mut row, |y, row| rows[y].copy_from_slice(row))
+            .unwrap();
+        assert_eq!(rows, [[1, 2, 3, 4, 5, 6], [7, 8, 9, 10, 11, 12]]);
+
+        assert!(matches!(
+            PNMImage::decode_rows_ascii(raw_img, &mut row[..5], |_, _| ()),
+            Err(ScratchTooSmall { required: 6 })
+        ));
+    }
+}
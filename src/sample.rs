@@ -0,0 +1,191 @@
+use crate::PNMImage;
+
+/// A type that samples of 8-bit and 16-bit images can be read as
+///
+/// This allows code to be written once for both sample depths, e.g. by calling
+/// [`PNMImage::pixel::<u16>`](PNMImage::pixel) regardless of the source image.
+pub trait Sample: Copy {
+    /// Converts a raw sample of an image with the given maximum pixel value.
+    fn from_sample(value: u16, maximum_pixel: u16) -> Self;
+}
+
+impl Sample for u8 {
+    /// Scales the sample from `0..=maximum_pixel` to `0..=255`.
+    fn from_sample(value: u16, maximum_pixel: u16) -> Self {
+        match maximum_pixel {
+            255 => value as u8,
+            0 => 0,
+            _ => {
+                let maximum_pixel = maximum_pixel as u32;
+                ((value as u32 * 255 + maximum_pixel / 2) / maximum_pixel).min(255) as u8
+            }
+        }
+    }
+}
+
+impl Sample for u16 {
+    /// Keeps the raw sample without scaling it.
+    fn from_sample(value: u16, _maximum_pixel: u16) -> Self {
+        value
+    }
+}
+
+impl PNMImage<'_> {
+    /// Returns the RGB samples of the pixel at the specified (x, y) coordinate, converted to `S`.
+    /// Returns `None` if the pixel is outside the bounds of the image.
+    ///
+    /// Samples read as `u8` are scaled from `0..=maximum_pixel` to `0..=255`, so pixels of bitmaps
+    /// read as 0 if black and 255 if white. Samples read as `u16` are raw, as big-endian values for
+    /// images with a maximum pixel value above 255, and 0 or 1 for bitmaps.
+    /// The gray value of grayscale images is replicated to all three channels. Further samples of
+    /// PAM images, such as alpha, are ignored.
+    pub fn pixel<S: Sample>(&self, x: usize, y: usize) -> Option<(S, S, S)> {
+        if x >= self.width() || y >= self.height() {
+            return None;
+        }
+        let row_len = self.row_len()?;
+        let row = self.pixel_data().get(y.checked_mul(row_len)?..)?;
+        self.decode_at(row, x)
+    }
+
+    /// Returns an iterator over all samples of the image in order, including further samples
+    /// of PAM images such as alpha.
+    ///
+    /// Samples of images with a maximum pixel value above 255 are read as big-endian 16-bit values,
+    /// pixels of bitmaps yield a single sample of 0 if black and 1 if white. The iterator ends
+    /// early if the image holds less pixel data than its header describes.
+    pub fn samples(&self) -> impl Iterator<Item = u16> + '_ {
+        let bits = self
+            .is_bitmap()
+            .then(|| self.pixels::<u16>().map(|(gray, _, _)| gray));
+        let bytes = (!self.is_bitmap()).then(|| {
+            self.raster()
+                .chunks_exact(self.sample_size())
+                .map(|s| match *s {
+                    [high, low] => u16::from_be_bytes([high, low]),
+                    [value] => value as u16,
+                    _ => unreachable!("samples are one or two bytes"),
+                })
+        });
+        bits.into_iter()
+            .flatten()
+            .chain(bytes.into_iter().flatten())
+    }
+
+    /// Like [`samples`](Self::samples), but scales every sample from `0..=maximum_pixel`
+    /// to the full `0..=65535` range, so images of different maximum pixel values compare equally.
+    pub fn samples_scaled(&self) -> impl Iterator<Item = u16> + '_ {
+        let maximum_pixel = self.maximum_pixel().clamp(1, 65535) as u32;
+        self.samples().map(move |s| {
+            ((s as u32 * 65535 + maximum_pixel / 2) / maximum_pixel).min(65535) as u16
+        })
+    }
+
+    /// Decodes pixel `x` of a row of pixel data into RGB samples.
+    /// Returns `None` if the row is too short.
+    pub(crate) fn decode_at<S: Sample>(&self, row: &[u8], x: usize) -> Option<(S, S, S)> {
+        if self.is_bitmap() {
+            // a set bit is black, which is a sample of 0
+            let white = (row.get(x / 8)? >> (7 - x % 8)) & 1 == 0;
+            let value = S::from_sample(white as u16, 1);
+            return Some((value, value, value));
+        }
+        let pixel_size = self.pixel_size();
+        let px = row.get(x * pixel_size..(x + 1) * pixel_size)?;
+        Some(self.decode_pixel(px))
+    }
+
+    /// Decodes the raw bytes of a single pixel into RGB samples. The image must not be a bitmap.
+    pub(crate) fn decode_pixel<S: Sample>(&self, px: &[u8]) -> (S, S, S) {
+        let maximum_pixel = self.maximum_pixel().min(65535) as u16;
+        let sample = |i: usize| {
+            let value = if self.sample_size() == 2 {
+                u16::from_be_bytes([px[2 * i], px[2 * i + 1]])
+            } else {
+                px[i] as u16
+            };
+            S::from_sample(value, maximum_pixel)
+        };
+        if self.channels() < 3 {
+            let gray = sample(0);
+            (gray, gray, gray)
+        } else {
+            (sample(0), sample(1), sample(2))
+        }
+    }
+
+    /// Returns an iterator over the RGB samples of all pixels in row-major order,
+    /// ending early if the image holds less pixel data than its header describes.
+    pub(crate) fn pixels<S: Sample>(&self) -> impl Iterator<Item = (S, S, S)> + '_ {
+        let width = self.width();
+        let row_len = self.row_len().unwrap_or(0).max(1);
+        self.pixel_data()
+            .chunks(row_len)
+            .take(self.height())
+            .flat_map(move |row| (0..width).map_while(move |x| self.decode_at(row, x)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn generic_pixels() {
+        let raw_img = include_bytes!("./binary.ppm");
+        let ppm_img = PNMImage::from_parse(raw_img).unwrap();
+        assert_eq!(ppm_img.pixel::<u8>(31, 7), Some((255, 0, 0)));
+        assert_eq!(ppm_img.pixel::<u16>(31, 7), Some((255, 0, 0)));
+        assert_eq!(ppm_img.pixel::<u16>(64, 0), None);
+
+        let deep_img = PNMImage::from_parse(
+            b"P6\n2 1\n65535\n\x12\x34\x00\x01\xff\xff\x00\x00\x00\x00\x00\x00",
+        )
+        .unwrap();
+        assert_eq!(deep_img.pixel::<u16>(0, 0), Some((0x1234, 0x0001, 0xffff)));
+        assert_eq!(deep_img.pixel::<u8>(0, 0), Some((0x12, 0x00, 0xff)));
+        assert_eq!(deep_img.pixel_rgb(0, 0), Some((0x12, 0x00, 0xff)));
+        assert_eq!(deep_img.pixel::<u16>(1, 0), Some((0, 0, 0)));
+        assert_eq!(deep_img.pixel::<u16>(0, 1), None);
+        let huge = PNMImage::from_parse(b"P6\n100000000000 100000000000\n255\n").unwrap();
+        assert_eq!(huge.pixel_rgb(5, 99999999999), None);
+
+        // 8-bit samples are scaled to the full range
+        let pbm_img = PNMImage::from_parse(b"P4\n2 1\n\x40").unwrap();
+        assert_eq!(pbm_img.pixel_rgb(0, 0), Some((255, 255, 255)));
+        assert_eq!(pbm_img.pixel::<u16>(0, 0), Some((1, 1, 1)));
+        assert_eq!(pbm_img.pixel_rgb(1, 0), Some((0, 0, 0)));
+        let pgm_img = PNMImage::from_parse(b"P5\n1 1\n15\n\x0f").unwrap();
+        assert_eq!(pgm_img.pixel_rgb(0, 0), Some((255, 255, 255)));
+        let mut out = [0; 6];
+        assert_eq!(pbm_img.thumbnail(2, 1, &mut out), Ok((2, 1)));
+        assert_eq!(out, [255, 255, 255, 0, 0, 0]);
+    }
+
+    #[test]
+    fn samples() {
+        let deep_img = PNMImage::from_parse(b"P5\n3 1\n1023\n\x03\xff\x00\x01\x02\x00").unwrap();
+        let mut samples = [0; 3];
+        samples
+            .iter_mut()
+            .zip(deep_img.samples())
+            .for_each(|(s, v)| *s = v);
+        assert_eq!(samples, [1023, 1, 512]);
+        assert_eq!(deep_img.samples_scaled().next(), Some(65535));
+        assert_eq!(deep_img.samples_scaled().nth(2), Some(32800));
+
+        let pam_img =
+            PNMImage::from_parse(b"P7\nWIDTH 1\nHEIGHT 1\nDEPTH 2\nMAXVAL 255\nENDHDR\n\x10\x20")
+                .unwrap();
+        assert!(pam_img.samples().eq([0x10, 0x20]));
+
+        let pbm_img = PNMImage::from_parse(b"P4\n3 2\n\x40\xff").unwrap();
+        assert!(pbm_img.samples().eq([1, 0, 1, 0, 0, 0]));
+        assert!(pbm_img.samples_scaled().eq([65535, 0, 65535, 0, 0, 0]));
+
+        let raw_img = include_bytes!("./binary.ppm");
+        let ppm_img = PNMImage::from_parse(raw_img).unwrap();
+        assert_eq!(ppm_img.samples().count(), 64 * 64 * 3);
+        assert_eq!(ppm_img.samples().max(), Some(255));
+    }
+}
@@ -0,0 +1,244 @@
+use crate::parallel::for_each_row;
+use crate::{BufferTooSmall, PNMImage};
+
+impl PNMImage<'_> {
+    /// Downscales the image to fit within `max_w` x `max_h`, preserving its aspect ratio.
+    ///
+    /// Every output pixel is the average of the source pixels it covers (a box filter),
+    /// which gives much smoother previews than nearest-neighbor sampling.
+    /// Images that already fit are copied unscaled. The thumbnail is written as 8-bit RGB
+    /// triples in row-major order, with samples scaled from `0..=maximum_pixel` to `0..=255`.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_w` - The maximum width of the thumbnail
+    /// * `max_h` - The maximum height of the thumbnail
+    /// * `out` - A buffer receiving the thumbnail
+    ///
+    /// # Returns
+    ///
+    /// A Result object containing the width and height of the thumbnail if successful,
+    /// otherwise the required size of `out`
+    pub fn thumbnail(
+        &self,
+        max_w: usize,
+        max_h: usize,
+        out: &mut [u8],
+    ) -> Result<(usize, usize), BufferTooSmall> {
+        self.thumbnail_with(max_w, max_h, out, false)
+    }
+
+    /// Downscales the image into `out`, in parallel if `parallel` is set.
+    pub(crate) fn thumbnail_with(
+        &self,
+        max_w: usize,
+        max_h: usize,
+        out: &mut [u8],
+        parallel: bool,
+    ) -> Result<(usize, usize), BufferTooSmall> {
+        let (width, height) = (self.width(), self.height());
+        let (thumb_w, thumb_h) = fit(width, height, max_w, max_h);
+        let required = thumb_w
+            .checked_mul(thumb_h)
+            .and_then(|len| len.checked_mul(3))
+            .ok_or(BufferTooSmall {
+                required: usize::MAX,
+            })?;
+        if out.len() < required {
+            return Err(BufferTooSmall { required });
+        }
+        // rows beyond the pixel data are black, so they are not visited
+        let rows = match self.row_len() {
+            Some(row_len) if row_len > 0 => self.pixel_data().len().div_ceil(row_len),
+            _ => 0,
+        };
+        // maps an output coordinate to the source coordinate it starts at, without overflowing
+        let source = |t: usize, len: usize, thumb_len: usize| {
+            (t as u128 * len as u128 / thumb_len as u128) as usize
+        };
+
+        for_each_row(&mut out[..required], thumb_w * 3, parallel, |ty, row| {
+            let (y0, y1) = (source(ty, height, thumb_h), source(ty + 1, height, thumb_h));
+            for (tx, px) in row.chunks_exact_mut(3).enumerate() {
+                let (x0, x1) = (source(tx, width, thumb_w), source(tx + 1, width, thumb_w));
+                let mut sum = [0u64; 3];
+                for y in y0..y1.min(rows) {
+                    for x in x0..x1 {
+                        let (r, g, b) = self.pixel_rgb(x, y).unwrap_or((0, 0, 0));
+                        sum[0] += r as u64;
+                        sum[1] += g as u64;
+                        sum[2] += b as u64;
+                    }
+                }
+                let count = (y1 - y0) as u128 * (x1 - x0) as u128;
+                for (dst, sum) in px.iter_mut().zip(sum) {
+                    *dst = ((sum as u128 + count / 2) / count) as u8;
+                }
+            }
+        });
+        Ok((thumb_w, thumb_h))
+    }
+
+    /// Upscales the image by an integer `factor`, repeating every pixel `factor` x `factor` times.
+    ///
+    /// No interpolation is performed, so pixel art stays crisp. The result is written as 8-bit RGB
+    /// triples in row-major order, with samples scaled from `0..=maximum_pixel` to `0..=255`.
+    /// Pixels missing from the pixel data are written as black.
+    ///
+    /// # Arguments
+    ///
+    /// * `factor` - How many times wider and taller the result is
+    /// * `out` - A buffer receiving the upscaled image
+    ///
+    /// # Returns
+    ///
+    /// A Result object containing the width and height of the upscaled image if successful,
+    /// otherwise the required size of `out`
+    pub fn upscale_integer(
+        &self,
+        factor: usize,
+        out: &mut [u8],
+    ) -> Result<(usize, usize), BufferTooSmall> {
+        self.upscale_integer_with(factor, out, false)
+    }
+
+    /// Upscales the image by `factor` into `out`, in parallel if `parallel` is set.
+    pub(crate) fn upscale_integer_with(
+        &self,
+        factor: usize,
+        out: &mut [u8],
+        parallel: bool,
+    ) -> Result<(usize, usize), BufferTooSmall> {
+        // a result too large to address can never fit into `out`
+        let too_large = BufferTooSmall {
+            required: usize::MAX,
+        };
+        let width = self.width().checked_mul(factor).ok_or(too_large)?;
+        let height = self.height().checked_mul(factor).ok_or(too_large)?;
+        let required = width
+            .checked_mul(height)
+            .and_then(|len| len.checked_mul(3))
+            .ok_or(too_large)?;
+        if out.len() < required {
+            return Err(BufferTooSmall { required });
+        }
+        if required == 0 {
+            return Ok((width, height));
+        }
+
+        let row_len = width * 3;
+        for_each_row(
+            &mut out[..required],
+            row_len * factor,
+            parallel,
+            |y, rows| {
+                // fill the first row of the block, then repeat it
+                let (first, rest) = rows.split_at_mut(row_len);
+                for (x, px) in first.chunks_exact_mut(3 * factor).enumerate() {
+                    let (r, g, b) = self.pixel_rgb(x, y).unwrap_or((0, 0, 0));
+                    for dst in px.chunks_exact_mut(3) {
+                        dst.copy_from_slice(&[r, g, b]);
+                    }
+                }
+                for row in rest.chunks_exact_mut(row_len) {
+                    row.copy_from_slice(first);
+                }
+            },
+        );
+        Ok((width, height))
+    }
+}
+
+/// Returns the largest dimensions within `max_w` x `max_h` with the aspect ratio of `width` x `height`,
+/// without upscaling. No dimension of a non-empty result is less than 1.
+fn fit(width: usize, height: usize, max_w: usize, max_h: usize) -> (usize, usize) {
+    if width == 0 || height == 0 || max_w == 0 || max_h == 0 {
+        return (0, 0);
+    }
+    let (width, height, max_w, max_h) =
+        (width as u128, height as u128, max_w as u128, max_h as u128);
+    let (w, h) = if width * max_h <= height * max_w {
+        let h = height.min(max_h);
+        ((width * h / height).max(1), h)
+    } else {
+        let w = width.min(max_w);
+        (w, (height * w / width).max(1))
+    };
+    (w as usize, h as usize)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn thumbnail() {
+        let raw_img = include_bytes!("./binary.ppm");
+        let ppm_img = PNMImage::from_parse(raw_img).unwrap();
+        let mut out = [0; 16 * 16 * 3];
+
+        assert_eq!(ppm_img.thumbnail(16, 100, &mut out), Ok((16, 16)));
+        // the source pixels at (4..8, 4..8) are all green
+        assert_eq!(&out[(16 + 1) * 3..][..3], &[0, 255, 0]);
+
+        assert_eq!(
+            ppm_img.thumbnail(32, 32, &mut out),
+            Err(BufferTooSmall {
+                required: 32 * 32 * 3
+            })
+        );
+    }
+
+    #[test]
+    fn upscale() {
+        let ppm_img = PNMImage::from_parse(b"P6\n2 1\n255\n\x01\x02\x03\x04\x05\x06").unwrap();
+        let mut out = [0; 4 * 2 * 3];
+        assert_eq!(ppm_img.upscale_integer(2, &mut out), Ok((4, 2)));
+        assert_eq!(
+            out,
+            [1, 2, 3, 1, 2, 3, 4, 5, 6, 4, 5, 6, 1, 2, 3, 1, 2, 3, 4, 5, 6, 4, 5, 6]
+        );
+
+        assert_eq!(
+            ppm_img.upscale_integer(3, &mut out),
+            Err(BufferTooSmall {
+                required: 6 * 3 * 3
+            })
+        );
+        assert_eq!(ppm_img.upscale_integer(0, &mut out), Ok((0, 0)));
+        assert_eq!(
+            ppm_img.upscale_integer(usize::MAX / 2, &mut out),
+            Err(BufferTooSmall {
+                required: usize::MAX
+            })
+        );
+        let huge_img = PNMImage::from_parse(b"P6\n4294967296 4294967296\n255\n").unwrap();
+        assert_eq!(
+            huge_img.upscale_integer(1, &mut out),
+            Err(BufferTooSmall {
+                required: usize::MAX
+            })
+        );
+    }
+
+    #[test]
+    fn thumbnail_averages() {
+        let ppm_img =
+            PNMImage::from_parse(b"P6\n4 2\n255\n\x00\x00\x00\xff\xff\xff\x10\x20\x30\x10\x20\x30\x00\x00\x00\xff\xff\xff\x10\x20\x30\x10\x20\x30")
+                .unwrap();
+        let mut out = [0; 6];
+        assert_eq!(ppm_img.thumbnail(2, 2, &mut out), Ok((2, 1)));
+        assert_eq!(out, [128, 128, 128, 0x10, 0x20, 0x30]);
+
+        // only the header is present, so all pixels are black
+        let huge = PNMImage::from_parse(b"P6\n100000000000 100000000000\n255\n").unwrap();
+        assert_eq!(huge.thumbnail(1, 1, &mut out), Ok((1, 1)));
+        assert_eq!(out[..3], [0, 0, 0]);
+        assert_eq!(
+            huge.thumbnail(usize::MAX, usize::MAX, &mut out),
+            Err(BufferTooSmall {
+                required: usize::MAX
+            })
+        );
+    }
+}
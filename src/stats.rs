@@ -0,0 +1,88 @@
+use crate::PNMImage;
+
+/// Statistics of the red, green and blue channels of an image, see [`PNMImage::stats`]
+///
+/// Every array holds the value of the red, green and blue channel in this order. Samples are
+/// unscaled, so their range depends on the maximum pixel value of the image.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ChannelStats {
+    /// The number of pixels the statistics cover
+    pub count: usize,
+    /// The smallest sample of every channel
+    pub min: [u16; 3],
+    /// The largest sample of every channel
+    pub max: [u16; 3],
+    /// The mean sample of every channel, rounded to the nearest integer
+    pub mean: [u16; 3],
+    /// The population variance of the samples of every channel, rounded down
+    pub variance: [u32; 3],
+}
+
+impl PNMImage<'_> {
+    /// Computes the minimum, maximum, mean and variance of every channel in a single pass.
+    ///
+    /// The gray value of grayscale images is counted for all three channels, pixels of bitmaps
+    /// read as 0 if black and 1 if white. Further samples of PAM images, such as alpha, are
+    /// ignored, as are pixels missing from the pixel data. All statistics are zero for empty images.
+    pub fn stats(&self) -> ChannelStats {
+        let mut count = 0usize;
+        let mut min = [u16::MAX; 3];
+        let mut max = [0; 3];
+        let mut sum = [0u64; 3];
+        let mut sum_sq = [0u128; 3];
+        for (r, g, b) in self.pixels::<u16>() {
+            count += 1;
+            for (c, value) in [r, g, b].into_iter().enumerate() {
+                min[c] = min[c].min(value);
+                max[c] = max[c].max(value);
+                sum[c] += value as u64;
+                sum_sq[c] += value as u128 * value as u128;
+            }
+        }
+        if count == 0 {
+            return ChannelStats::default();
+        }
+
+        let n = count as u128;
+        let mean = sum.map(|sum| ((sum as u128 + n / 2) / n) as u16);
+        let mut variance = [0; 3];
+        for (c, variance) in variance.iter_mut().enumerate() {
+            let sum = sum[c] as u128;
+            // n * sum_sq >= sum^2 for any samples, so this cannot underflow
+            *variance = ((n * sum_sq[c] - sum * sum) / (n * n)) as u32;
+        }
+        ChannelStats {
+            count,
+            min,
+            max,
+            mean,
+            variance,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn stats() {
+        let ppm_img =
+            PNMImage::from_parse(b"P6\n2 2\n255\n\x00\x10\xff\x02\x10\xff\x04\x10\x00\x06\x10\x00")
+                .unwrap();
+        let stats = ppm_img.stats();
+        assert_eq!(stats.count, 4);
+        assert_eq!(stats.min, [0, 0x10, 0]);
+        assert_eq!(stats.max, [6, 0x10, 255]);
+        assert_eq!(stats.mean, [3, 0x10, 128]);
+        assert_eq!(stats.variance, [5, 0, 16256]);
+
+        let deep_img = PNMImage::from_parse(b"P5\n2 1\n65535\n\x00\x00\xff\xff").unwrap();
+        let stats = deep_img.stats();
+        assert_eq!(stats.mean, [32768; 3]);
+        assert_eq!(stats.variance, [1073709056; 3]);
+
+        let empty = PNMImage::from_parse(b"P5\n0 0\n255\n").unwrap();
+        assert_eq!(empty.stats(), ChannelStats::default());
+    }
+}
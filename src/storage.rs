@@ -0,0 +1,267 @@
+use embedded_storage::nor_flash::ReadNorFlash;
+
+use crate::{PNMHeader, PNMImage, ReadError};
+
+/// Size of the buffer used to align reads for flash with a read size above one byte
+const BOUNCE_LEN: usize = 64;
+
+/// Reads `out.len()` bytes starting at `offset`, regardless of the alignment required by `flash`.
+///
+/// Flash with a read size above 64 bytes is rejected at compile time.
+fn read_at<F: ReadNorFlash>(
+    flash: &mut F,
+    offset: usize,
+    out: &mut [u8],
+) -> Result<(), ReadError<F::Error>> {
+    const {
+        assert!(
+            F::READ_SIZE <= BOUNCE_LEN,
+            "flash read size exceeds 64 bytes"
+        )
+    };
+    let address = |pos: usize| u32::try_from(pos).map_err(|_| ReadError::OutOfBounds);
+    if F::READ_SIZE <= 1 {
+        return flash.read(address(offset)?, out).map_err(ReadError::Io);
+    }
+    let block = BOUNCE_LEN / F::READ_SIZE * F::READ_SIZE;
+    let mut bounce = [0; BOUNCE_LEN];
+    let mut done = 0;
+    while done < out.len() {
+        let pos = offset + done;
+        let skip = pos % F::READ_SIZE;
+        // rounding up must not read past the end of the flash
+        let len = (skip + out.len() - done)
+            .div_ceil(F::READ_SIZE)
+            .saturating_mul(F::READ_SIZE)
+            .min(block)
+            .min(flash.capacity().saturating_sub(pos - skip));
+        if len <= skip {
+            return Err(ReadError::OutOfBounds);
+        }
+        flash
+            .read(address(pos - skip)?, &mut bounce[..len])
+            .map_err(ReadError::Io)?;
+        let n = (len - skip).min(out.len() - done);
+        out[done..done + n].copy_from_slice(&bounce[skip..skip + n]);
+        done += n;
+    }
+    Ok(())
+}
+
+impl<'a> PNMImage<'a> {
+    /// Reads the header of a PNM image stored in flash at `offset` and returns an image reading its pixels on demand
+    ///
+    /// Only the header is stored in `scratch`; rows and pixels are read from `flash` when requested,
+    /// so images in flash that is not memory-mapped, such as external QSPI flash, can be drawn directly.
+    /// Flash requiring aligned reads is supported by reading through a small internal buffer.
+    ///
+    /// # Arguments
+    ///
+    /// * `flash` - The flash the image is stored in
+    /// * `offset` - The address of the first byte of the image
+    /// * `scratch` - A buffer large enough to hold the header
+    ///
+    /// # Returns
+    ///
+    /// A Result object containing the StorageImage if successful, otherwise a ReadError.
+    /// Flash with a read size above 64 bytes is rejected at compile time.
+    pub fn from_storage<F: ReadNorFlash>(
+        mut flash: F,
+        offset: usize,
+        scratch: &'a mut [u8],
+    ) -> Result<StorageImage<'a, F>, ReadError<F::Error>> {
+        let mut len = 0;
+        loop {
+            let byte = scratch
+                .get_mut(len..len + 1)
+                .ok_or(ReadError::BufferTooSmall { required: len + 1 })?;
+            read_at(&mut flash, offset + len, byte)?;
+            len += 1;
+            if crate::read::header_complete(&scratch[..len])?.is_some() {
+                break;
+            }
+        }
+        let scratch: &'a [u8] = scratch;
+        let image = Self::from_parse(&scratch[..len])?;
+        Ok(StorageImage {
+            flash,
+            data_offset: offset + len,
+            image,
+        })
+    }
+}
+
+/// A PNM image whose pixel data is read on demand from flash, created by [`PNMImage::from_storage`]
+#[derive(Debug)]
+pub struct StorageImage<'a, F> {
+    flash: F,
+    data_offset: usize,
+    image: PNMImage<'a>,
+}
+
+impl<'a, F: ReadNorFlash> StorageImage<'a, F> {
+    /// Returns the header of the image.
+    pub fn header(&self) -> PNMHeader<'a> {
+        self.image.header()
+    }
+
+    /// Returns the width of the image.
+    pub fn width(&self) -> usize {
+        self.image.width()
+    }
+
+    /// Returns the height of the image.
+    pub fn height(&self) -> usize {
+        self.image.height()
+    }
+
+    /// Returns the maximum pixel value of the image.
+    pub fn maximum_pixel(&self) -> usize {
+        self.image.maximum_pixel()
+    }
+
+    /// Returns the comment associated with the image.
+    pub fn comment(&self) -> &'a str {
+        self.image.header().comment
+    }
+
+    /// Returns the number of bytes of a single row of pixel data.
+    pub fn row_len(&self) -> usize {
+        // the header was checked to describe a raster whose size fits in a usize
+        self.image.row_len().unwrap_or_default()
+    }
+
+    /// Reads row `y` of the pixel data into `row`.
+    pub fn read_row(&mut self, y: usize, row: &mut [u8]) -> Result<(), ReadError<F::Error>> {
+        if y >= self.height() {
+            return Err(ReadError::OutOfBounds);
+        }
+        let required = self.row_len();
+        let row = row
+            .get_mut(..required)
+            .ok_or(ReadError::BufferTooSmall { required })?;
+        read_at(&mut self.flash, self.data_offset + y * required, row)
+    }
+
+    /// Reads the RGB values of the pixel at the specified (x, y) coordinate,
+    /// with the same conversions as [`PNMImage::pixel_rgb`].
+    pub fn pixel_rgb(&mut self, x: usize, y: usize) -> Result<(u8, u8, u8), ReadError<F::Error>> {
+        if x >= self.width() || y >= self.height() {
+            return Err(ReadError::OutOfBounds);
+        }
+        let row_start = self.data_offset + y * self.row_len();
+        if self.image.is_bitmap() {
+            let mut byte = [0];
+            read_at(&mut self.flash, row_start + x / 8, &mut byte)?;
+            return Ok(self.image.decode_at(&byte, x % 8).unwrap_or_default());
+        }
+        // only the first three samples are needed for RGB
+        let pixel_size = self.image.pixel_size();
+        let mut px = [0; 6];
+        let px = &mut px[..pixel_size.min(3 * self.image.sample_size())];
+        read_at(&mut self.flash, row_start + x * pixel_size, px)?;
+        Ok(self.image.decode_pixel(px))
+    }
+
+    /// Returns the underlying flash.
+    pub fn into_inner(self) -> F {
+        self.flash
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use embedded_storage::nor_flash::{ErrorType, NorFlashErrorKind};
+
+    use super::*;
+
+    /// Flash backed by a byte slice, only allowing reads aligned to `READ_SIZE`,
+    /// except for a shorter read up to its end
+    struct Flash<'a, const READ_SIZE: usize> {
+        data: &'a [u8],
+        reads: usize,
+    }
+
+    impl<const READ_SIZE: usize> ErrorType for Flash<'_, READ_SIZE> {
+        type Error = NorFlashErrorKind;
+    }
+
+    impl<const READ_SIZE: usize> ReadNorFlash for Flash<'_, READ_SIZE> {
+        const READ_SIZE: usize = READ_SIZE;
+
+        fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), Self::Error> {
+            let offset = offset as usize;
+            let to_end = offset + bytes.len() == self.data.len();
+            if !offset.is_multiple_of(READ_SIZE)
+                || !(bytes.len().is_multiple_of(READ_SIZE) || to_end)
+            {
+                return Err(NorFlashErrorKind::NotAligned);
+            }
+            let src = self
+                .data
+                .get(offset..offset + bytes.len())
+                .ok_or(NorFlashErrorKind::OutOfBounds)?;
+            bytes.copy_from_slice(src);
+            self.reads += 1;
+            Ok(())
+        }
+
+        fn capacity(&self) -> usize {
+            self.data.len()
+        }
+    }
+
+    #[test]
+    fn from_storage() {
+        let mut data = [0xff; 4 + 12352];
+        let raw_img = include_bytes!("./binary.ppm");
+        data[4..4 + raw_img.len()].copy_from_slice(raw_img);
+        let flash = Flash::<1> {
+            data: &data,
+            reads: 0,
+        };
+        let mut scratch = [0; 64];
+        let mut image = PNMImage::from_storage(flash, 4, &mut scratch).unwrap();
+
+        assert_eq!(image.width(), 64);
+        assert_eq!(
+            image.comment(),
+            "# Created by GIMP version 2.10.34 PNM plug-in"
+        );
+        assert_eq!(image.pixel_rgb(56, 56).unwrap(), (0, 0, 255));
+        let mut row = [0; 64 * 3];
+        image.read_row(31, &mut row).unwrap();
+        assert_eq!(row[31 * 3..32 * 3], [255, 255, 255]);
+        assert!(matches!(
+            image.read_row(64, &mut row),
+            Err(ReadError::OutOfBounds)
+        ));
+    }
+
+    #[test]
+    fn aligned_storage() {
+        let data = *b"P6\n# aligned\n3 1\n255\n\x01\x02\x03\x04\x05\x06\x07\x08\x09\0\0\0";
+        let flash = Flash::<4> {
+            data: &data,
+            reads: 0,
+        };
+        let mut scratch = [0; 32];
+        let mut image = PNMImage::from_storage(flash, 0, &mut scratch).unwrap();
+        assert_eq!(image.pixel_rgb(1, 0).unwrap(), (4, 5, 6));
+        let mut row = [0; 9];
+        image.read_row(0, &mut row).unwrap();
+        assert_eq!(row, [1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        // the header is read byte by byte, the unaligned row in a single read
+        assert_eq!(image.into_inner().reads, 21 + 1 + 1);
+
+        // the image ends where the flash ends, before the end of its last aligned block
+        let flash = Flash::<4> {
+            data: &data[..30],
+            reads: 0,
+        };
+        let mut image = PNMImage::from_storage(flash, 0, &mut scratch).unwrap();
+        image.read_row(0, &mut row).unwrap();
+        assert_eq!(row, [1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        assert_eq!(image.pixel_rgb(2, 0).unwrap(), (7, 8, 9));
+    }
+}
@@ -0,0 +1,184 @@
+use crate::PNMImage;
+
+/// A rectangular view into a [`PNMImage`], such as a single cell of a sprite sheet
+#[derive(Clone, Copy, Debug)]
+pub struct SubImage<'a> {
+    image: &'a PNMImage<'a>,
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+}
+
+impl<'a> SubImage<'a> {
+    /// Returns the width of the view.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the height of the view.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the (x, y) coordinate of the view's top left corner within the parent image.
+    pub fn origin(&self) -> (usize, usize) {
+        (self.x, self.y)
+    }
+
+    /// Returns the RGB values of the pixel at the specified (x, y) coordinate, relative to the view.
+    /// Returns `None` if the pixel is outside the bounds of the view.
+    pub fn pixel_rgb(&self, x: usize, y: usize) -> Option<(u8, u8, u8)> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        self.image.pixel_rgb(self.x + x, self.y + y)
+    }
+
+    /// Returns the raw bytes of row `y` of the view.
+    /// Returns `None` if the row is outside the bounds of the view or not backed by pixel data,
+    /// and for bitmaps, as their pixels do not start at byte boundaries.
+    pub fn row(&self, y: usize) -> Option<&'a [u8]> {
+        if y >= self.height || self.image.is_bitmap() {
+            return None;
+        }
+        let pixel_size = self.image.pixel_size();
+        let start = (self.y + y)
+            .checked_mul(self.image.width())
+            .and_then(|idx| idx.checked_add(self.x)?.checked_mul(pixel_size))?;
+        let len = self.width.checked_mul(pixel_size)?;
+        self.image.pixel_data().get(start..start.checked_add(len)?)
+    }
+}
+
+impl<'a> PNMImage<'a> {
+    /// Returns a view of the `width` x `height` rectangle whose top left corner is at (x, y).
+    /// Returns `None` if the rectangle does not fit within the image.
+    pub fn sub_image(
+        &'a self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    ) -> Option<SubImage<'a>> {
+        if x.checked_add(width)? > self.width() || y.checked_add(height)? > self.height() {
+            return None;
+        }
+        Some(SubImage {
+            image: self,
+            x,
+            y,
+            width,
+            height,
+        })
+    }
+
+    /// Returns the `tile_w` x `tile_h` cell at column `col` and row `row` of a sprite sheet.
+    /// Returns `None` if the cell does not lie completely within the image, or if a tile dimension is zero.
+    pub fn tile(
+        &'a self,
+        tile_w: usize,
+        tile_h: usize,
+        col: usize,
+        row: usize,
+    ) -> Option<SubImage<'a>> {
+        if tile_w == 0 || tile_h == 0 {
+            return None;
+        }
+        self.sub_image(
+            col.checked_mul(tile_w)?,
+            row.checked_mul(tile_h)?,
+            tile_w,
+            tile_h,
+        )
+    }
+
+    /// Returns an iterator over all complete `tile_w` x `tile_h` cells of a sprite sheet, in row-major order.
+    ///
+    /// Partial cells at the right and bottom edges are skipped. Yields nothing if a tile dimension is zero.
+    pub fn tiles(&'a self, tile_w: usize, tile_h: usize) -> impl Iterator<Item = SubImage<'a>> {
+        let (cols, rows) = if tile_w == 0 || tile_h == 0 {
+            (0, 0)
+        } else {
+            (self.width() / tile_w, self.height() / tile_h)
+        };
+        (0..cols * rows).filter_map(move |i| self.tile(tile_w, tile_h, i % cols, i / cols))
+    }
+
+    /// Returns the smallest rectangle containing all pixels that differ from `background`
+    /// by more than `tolerance` in any channel, as `(x, y, width, height)`.
+    /// Returns `None` if all pixels are background.
+    ///
+    /// Samples are scaled from `0..=maximum_pixel` to `0..=255` before they are compared.
+    /// The rectangle can be passed to [`sub_image`](Self::sub_image) to crop the image.
+    pub fn bounding_box(
+        &self,
+        background: (u8, u8, u8),
+        tolerance: u8,
+    ) -> Option<(usize, usize, usize, usize)> {
+        let width = self.width().max(1);
+        let (bg_r, bg_g, bg_b) = background;
+        let differs = |a: u8, b: u8| a.abs_diff(b) > tolerance;
+        let mut bounds: Option<(usize, usize, usize, usize)> = None;
+        for (i, (r, g, b)) in self.pixels::<u8>().enumerate() {
+            if !differs(r, bg_r) && !differs(g, bg_g) && !differs(b, bg_b) {
+                continue;
+            }
+            let (x, y) = (i % width, i / width);
+            let (x0, y0, x1, y1) = bounds.unwrap_or((x, y, x, y));
+            bounds = Some((x0.min(x), y0, x1.max(x), y1.max(y)));
+        }
+        bounds.map(|(x0, y0, x1, y1)| (x0, y0, x1 - x0 + 1, y1 - y0 + 1))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn tiles() {
+        let raw_img = include_bytes!("./binary.ppm");
+        let ppm_img = PNMImage::from_parse(raw_img).unwrap();
+
+        assert_eq!(ppm_img.tiles(8, 8).count(), 64);
+        assert_eq!(ppm_img.tiles(24, 24).count(), 4);
+        assert_eq!(ppm_img.tiles(0, 8).count(), 0);
+
+        let green = ppm_img.tile(8, 8, 0, 0).unwrap();
+        assert_eq!(green.pixel_rgb(7, 7), Some((0, 255, 0)));
+        assert_eq!(green.pixel_rgb(8, 7), None);
+
+        let red = ppm_img.tile(8, 8, 3, 0).unwrap();
+        assert_eq!(red.origin(), (24, 0));
+        assert_eq!(red.pixel_rgb(7, 7), Some((255, 0, 0)));
+        assert_eq!(red.row(7).unwrap().len(), 8 * 3);
+        assert_eq!(red.row(7).unwrap()[21..], [255, 0, 0]);
+
+        let blue = ppm_img.tiles(8, 8).nth(7 * 8 + 7).unwrap();
+        assert_eq!(blue.pixel_rgb(0, 0), Some((0, 0, 255)));
+
+        assert!(ppm_img.tile(8, 8, 8, 0).is_none());
+        assert!(ppm_img.sub_image(60, 0, 5, 1).is_none());
+
+        let huge = PNMImage::from_parse(b"P6\n100000000000 100000000000\n255\n").unwrap();
+        let view = huge.sub_image(0, 99999999998, 8, 2).unwrap();
+        assert_eq!(view.row(1), None);
+    }
+
+    #[test]
+    fn bounding_box() {
+        let gray_img =
+            PNMImage::from_parse(b"P5\n4 3\n255\n\xff\xff\xff\xff\xff\x10\xfa\xff\xff\xff\x20\xff")
+                .unwrap();
+        let white = (255, 255, 255);
+        assert_eq!(gray_img.bounding_box(white, 0), Some((1, 1, 2, 2)));
+        assert_eq!(gray_img.bounding_box(white, 8), Some((1, 1, 2, 2)));
+        assert_eq!(gray_img.bounding_box(white, 240), None);
+        assert_eq!(gray_img.bounding_box((0, 0, 0), 250), Some((0, 0, 4, 3)));
+
+        let (x, y, w, h) = gray_img.bounding_box(white, 0).unwrap();
+        let cropped = gray_img.sub_image(x, y, w, h).unwrap();
+        assert_eq!(cropped.pixel_rgb(0, 0), Some((0x10, 0x10, 0x10)));
+    }
+}
@@ -0,0 +1,159 @@
+//! Helpers for comparing rendered images against golden images in tests.
+//!
+//! When images do not match, [`assert_images_match`] writes a diff image to the temporary
+//! directory, highlighting mismatched pixels in red on a dimmed copy of the expected image.
+
+use std::fmt::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::PNMImage;
+
+/// The number of mismatched pixels listed in the panic message of [`assert_images_match`]
+const MAX_REPORTED: usize = 10;
+
+/// A pixel that differs between two images, see [`mismatches`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Mismatch {
+    /// The x coordinate of the pixel
+    pub x: usize,
+    /// The y coordinate of the pixel
+    pub y: usize,
+    /// The RGB values of the pixel in the actual image, or `None` if it is missing
+    pub actual: Option<(u8, u8, u8)>,
+    /// The RGB values of the pixel in the expected image, or `None` if it is missing
+    pub expected: Option<(u8, u8, u8)>,
+}
+
+/// Returns an iterator over the pixels whose samples differ by more than `tolerance`
+/// in any channel, in row-major order.
+///
+/// The images are compared as 8-bit RGB, for the area of the larger image.
+pub fn mismatches<'a>(
+    actual: &'a PNMImage<'_>,
+    expected: &'a PNMImage<'_>,
+    tolerance: u8,
+) -> impl Iterator<Item = Mismatch> + 'a {
+    let width = actual.width().max(expected.width());
+    let height = actual.height().max(expected.height());
+    (0..height)
+        .flat_map(move |y| (0..width).map(move |x| (x, y)))
+        .filter_map(move |(x, y)| {
+            let (a, e) = (actual.pixel_rgb(x, y), expected.pixel_rgb(x, y));
+            let differs = match (a, e) {
+                (Some(a), Some(e)) => {
+                    let diff = |a: u8, e: u8| a.abs_diff(e) > tolerance;
+                    diff(a.0, e.0) || diff(a.1, e.1) || diff(a.2, e.2)
+                }
+                _ => true,
+            };
+            differs.then_some(Mismatch {
+                x,
+                y,
+                actual: a,
+                expected: e,
+            })
+        })
+}
+
+/// Asserts that the samples of `actual` and `expected` differ by at most `tolerance` in every pixel
+///
+/// # Panics
+///
+/// Panics if the images differ in size or pixels. The panic message lists the first mismatched
+/// pixels and the path of a binary PPM diff image, in which mismatched pixels are red.
+#[track_caller]
+pub fn assert_images_match(actual: &PNMImage<'_>, expected: &PNMImage<'_>, tolerance: u8) {
+    let (width, height) = (expected.width(), expected.height());
+    if (actual.width(), actual.height()) != (width, height) {
+        panic!(
+            "image sizes differ: actual {}x{}, expected {}x{}",
+            actual.width(),
+            actual.height(),
+            width,
+            height
+        );
+    }
+    let count = mismatches(actual, expected, tolerance).count();
+    if count == 0 {
+        return;
+    }
+
+    let mut message = format!("{} of {} pixels differ", count, width * height);
+    for m in mismatches(actual, expected, tolerance).take(MAX_REPORTED) {
+        let _ = write!(
+            message,
+            "\n  ({}, {}): actual {:?}, expected {:?}",
+            m.x, m.y, m.actual, m.expected
+        );
+    }
+    if count > MAX_REPORTED {
+        let _ = write!(message, "\n  ...");
+    }
+    match write_diff(actual, expected, tolerance) {
+        Ok(path) => {
+            let _ = write!(message, "\ndiff image written to {}", path.display());
+        }
+        Err(err) => {
+            let _ = write!(message, "\nfailed to write diff image: {}", err);
+        }
+    }
+    panic!("{}", message);
+}
+
+/// Writes the diff image into the temporary directory and returns its path.
+fn write_diff(
+    actual: &PNMImage<'_>,
+    expected: &PNMImage<'_>,
+    tolerance: u8,
+) -> std::io::Result<PathBuf> {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let (width, height) = (expected.width(), expected.height());
+    let mut bytes = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+    let header_len = bytes.len();
+    for y in 0..height {
+        for x in 0..width {
+            // a dimmed gray copy of the expected image as background
+            let gray = expected.pixel_gray(x, y).unwrap_or(0) / 4;
+            bytes.extend_from_slice(&[gray; 3]);
+        }
+    }
+    for m in mismatches(actual, expected, tolerance) {
+        let idx = header_len + (m.y * width + m.x) * 3;
+        bytes[idx..idx + 3].copy_from_slice(&[255, 0, 0]);
+    }
+    let path = std::env::temp_dir().join(format!(
+        "micropnm_diff_{}_{}.ppm",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    std::fs::write(&path, bytes)?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn images_match() {
+        let expected = PNMImage::from_parse(b"P6\n2 1\n255\n\x10\x20\x30\x40\x50\x60").unwrap();
+        let actual = PNMImage::from_parse(b"P6\n2 1\n255\n\x11\x20\x30\x40\x50\x70").unwrap();
+        assert_images_match(&expected, &expected, 0);
+        assert_images_match(&actual, &expected, 16);
+        assert!(mismatches(&actual, &expected, 1).eq([Mismatch {
+            x: 1,
+            y: 0,
+            actual: Some((0x40, 0x50, 0x70)),
+            expected: Some((0x40, 0x50, 0x60)),
+        }]));
+    }
+
+    #[test]
+    #[should_panic(expected = "1 of 2 pixels differ\n  (1, 0): actual")]
+    fn images_differ() {
+        let expected = PNMImage::from_parse(b"P6\n2 1\n255\n\x10\x20\x30\x40\x50\x60").unwrap();
+        let actual = PNMImage::from_parse(b"P6\n2 1\n255\n\x10\x20\x30\x40\x50\x70").unwrap();
+        assert_images_match(&actual, &expected, 0);
+    }
+}
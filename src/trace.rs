@@ -0,0 +1,14 @@
+//! Debug events emitted while parsing, enabled by the `trace` or `defmt` feature.
+//!
+//! Events go through `defmt` if the `defmt` feature is enabled and through `log` if only the
+//! `trace` feature is. Without either feature the [`trace!`] macro expands to nothing.
+
+/// Emits a debug-level event, taking the same arguments as `log::debug!`.
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "defmt")]
+        defmt::debug!($($arg)*);
+        #[cfg(all(feature = "trace", not(feature = "defmt")))]
+        log::debug!($($arg)*);
+    };
+}
@@ -0,0 +1,122 @@
+use crate::PNMImage;
+
+/// Error type that represents the different inconsistencies found by [`PNMImage::validate`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ValidationError {
+    /// The width, height or depth of the image is zero
+    ZeroDimension,
+    /// The maximum pixel value is outside of `1..=65535`
+    InvalidMaximumPixel(usize),
+    /// The size of the pixel data described by the header does not fit into memory
+    DimensionsTooLarge,
+    /// The pixel data is shorter or longer than described by the header
+    DataLengthMismatch {
+        /// The number of bytes described by the header
+        expected: usize,
+        /// The number of bytes present
+        actual: usize,
+    },
+    /// A sample is larger than the maximum pixel value
+    SampleOutOfRange {
+        /// The x coordinate of the offending pixel
+        x: usize,
+        /// The y coordinate of the offending pixel
+        y: usize,
+        /// The offending sample value
+        value: u16,
+    },
+}
+
+impl PNMImage<'_> {
+    /// Checks that the header and pixel data of the image are consistent with each other
+    ///
+    /// Parsing is lenient and only inspects the header, so this should be used as a strictness
+    /// gate before trusting images from third parties. Note that trailing bytes after the pixel
+    /// data are reported as a length mismatch.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the image is consistent, otherwise the first ValidationError found
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        if self.width() == 0 || self.height() == 0 || self.channels() == 0 {
+            return Err(ValidationError::ZeroDimension);
+        }
+        let maximum_pixel = self.maximum_pixel();
+        if !(1..=65535).contains(&maximum_pixel) {
+            return Err(ValidationError::InvalidMaximumPixel(maximum_pixel));
+        }
+        let expected = self
+            .raster_len()
+            .ok_or(ValidationError::DimensionsTooLarge)?;
+        let actual = self.pixel_data().len();
+        if expected != actual {
+            return Err(ValidationError::DataLengthMismatch { expected, actual });
+        }
+
+        if self.is_bitmap() || maximum_pixel == 255 || maximum_pixel == 65535 {
+            // no sample can exceed the maximum pixel value
+            return Ok(());
+        }
+        let samples = self.pixel_data().chunks_exact(self.sample_size());
+        for (idx, value) in samples.map(crate::sample_value).enumerate() {
+            if value as usize > maximum_pixel {
+                let pixel = idx / self.channels();
+                return Err(ValidationError::SampleOutOfRange {
+                    x: pixel % self.width(),
+                    y: pixel / self.width(),
+                    value,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn validate() {
+        let raw_img = include_bytes!("./binary.ppm");
+        let ppm_img = PNMImage::from_parse(raw_img).unwrap();
+        assert!(ppm_img.validate().is_ok());
+
+        let validate = |bytes| PNMImage::from_parse(bytes).unwrap().validate();
+        assert_eq!(
+            validate(b"P6\n0 1\n255\n"),
+            Err(ValidationError::ZeroDimension)
+        );
+        assert_eq!(
+            validate(b"P6\n1 1\n0\n\x00\x00\x00"),
+            Err(ValidationError::InvalidMaximumPixel(0))
+        );
+        assert_eq!(
+            validate(b"P6\n1 1\n70000\n\x00\x00\x00\x00\x00\x00"),
+            Err(ValidationError::InvalidMaximumPixel(70000))
+        );
+        assert_eq!(
+            validate(b"P6\n1 2\n255\n\x00\x00\x00"),
+            Err(ValidationError::DataLengthMismatch {
+                expected: 6,
+                actual: 3
+            })
+        );
+        assert_eq!(
+            validate(b"P6\n2 1\n15\n\x00\x00\x00\x00\x10\x00"),
+            Err(ValidationError::SampleOutOfRange {
+                x: 1,
+                y: 0,
+                value: 16
+            })
+        );
+        assert_eq!(
+            validate(b"P6\n1 1\n1000\n\x03\xe8\x03\xe9\x00\x00"),
+            Err(ValidationError::SampleOutOfRange {
+                x: 0,
+                y: 0,
+                value: 1001
+            })
+        );
+    }
+}
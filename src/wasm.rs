@@ -0,0 +1,137 @@
+//! Bindings for decoding images from JavaScript through `wasm-bindgen`.
+//!
+//! ```js
+//! const image = new PnmImage(new Uint8Array(await response.arrayBuffer()));
+//! const data = new ImageData(image.decodeToRgba(), image.width, image.height);
+//! canvas.getContext("2d").putImageData(data, 0, 0);
+//! ```
+
+use js_sys::Uint8ClampedArray;
+use wasm_bindgen::prelude::*;
+
+use crate::{PNMError, PNMImage, PixelFormat};
+
+/// The reasons an image cannot be decoded for JavaScript
+#[derive(Debug, PartialEq, Eq)]
+enum DecodeError {
+    /// The image is not a valid PNM image
+    Parse(PNMError),
+    /// The width or height of the image does not fit into a `u32`
+    DimensionsTooLarge,
+}
+
+impl From<PNMError> for DecodeError {
+    fn from(err: PNMError) -> Self {
+        DecodeError::Parse(err)
+    }
+}
+
+/// A decoded PNM image, exported to JavaScript as `PnmImage`
+#[wasm_bindgen(js_name = PnmImage)]
+pub struct WasmImage {
+    width: u32,
+    height: u32,
+    rgba: Vec<u8>,
+}
+
+#[wasm_bindgen(js_class = PnmImage)]
+impl WasmImage {
+    /// Parses and decodes a binary or plain PNM image.
+    #[wasm_bindgen(constructor)]
+    pub fn new(bytes: &[u8]) -> Result<WasmImage, JsError> {
+        decode(bytes).map_err(|err| JsError::new(&format!("invalid PNM image: {:?}", err)))
+    }
+
+    /// Returns the width of the image.
+    #[wasm_bindgen(getter)]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the height of the image.
+    #[wasm_bindgen(getter)]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the pixels as RGBA, ready to be passed to the `ImageData` constructor.
+    #[wasm_bindgen(js_name = decodeToRgba)]
+    pub fn decode_to_rgba(&self) -> Uint8ClampedArray {
+        Uint8ClampedArray::from(&self.rgba[..])
+    }
+}
+
+/// Parses and decodes a binary or plain PNM image into RGBA pixels, ready to be passed to the
+/// `ImageData` constructor along with the width and height of the image.
+#[wasm_bindgen(js_name = decodeToRgba)]
+pub fn decode_to_rgba(bytes: &[u8]) -> Result<Uint8ClampedArray, JsError> {
+    Ok(WasmImage::new(bytes)?.decode_to_rgba())
+}
+
+/// Decodes `bytes` into RGBA pixels, without touching JavaScript.
+///
+/// Samples are scaled to 8 bits, bitmaps decode to black and white and only images whose tuple
+/// type has alpha are not fully opaque, see [`PixelFormat::Rgba8888`].
+fn decode(bytes: &[u8]) -> Result<WasmImage, DecodeError> {
+    let mut scratch = Vec::new();
+    let image = match PNMImage::from_parse(bytes) {
+        Err(PNMError::UnsupportedPNMFormat) => {
+            let required = match PNMImage::from_parse_ascii(bytes, &mut []) {
+                Err(PNMError::ScratchTooSmall { required }) => required,
+                result => result.map(|_| 0)?,
+            };
+            scratch.resize(required, 0);
+            PNMImage::from_parse_ascii(bytes, &mut scratch)?
+        }
+        result => result?,
+    };
+    if image
+        .raster_len()
+        .is_none_or(|len| image.pixel_data().len() < len)
+    {
+        return Err(PNMError::UnexpectedEOF.into());
+    }
+    let too_large = |_| DecodeError::DimensionsTooLarge;
+    let width = u32::try_from(image.width()).map_err(too_large)?;
+    let height = u32::try_from(image.height()).map_err(too_large)?;
+    let mut rgba = vec![0; PixelFormat::Rgba8888.buffer_len(image.width(), image.height())];
+    // the buffer was sized for the image
+    let _ = image.decode_into(PixelFormat::Rgba8888, &mut rgba);
+    Ok(WasmImage {
+        width,
+        height,
+        rgba,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn decode_rgba() {
+        let image = decode(include_bytes!("./binary.ppm")).unwrap();
+        assert_eq!((image.width(), image.height()), (64, 64));
+        let idx = (7 * 64 + 31) * 4;
+        assert_eq!(image.rgba[idx..idx + 4], [255, 0, 0, 255]);
+
+        let image = decode(b"P2\n2 1\n255\n16 32\n").unwrap();
+        assert_eq!(image.rgba, [16, 16, 16, 255, 32, 32, 32, 255]);
+        assert_eq!(
+            decode(b"P6\n2 1\n255\n").err(),
+            Some(DecodeError::Parse(PNMError::UnexpectedEOF))
+        );
+        assert_eq!(
+            decode(b"P6\n4294967296 0\n255\n").err(),
+            Some(DecodeError::DimensionsTooLarge)
+        );
+
+        // set bits of bitmaps are black
+        let image = decode(b"P4\n2 1\n\x80").unwrap();
+        assert_eq!(image.rgba, [0, 0, 0, 255, 255, 255, 255, 255]);
+        let image = decode(b"P2\n2 1\n15\n15 5\n").unwrap();
+        assert_eq!(image.rgba, [255, 255, 255, 255, 85, 85, 85, 255]);
+        let image = decode(b"P5\n1 1\n65535\n\x80\x00").unwrap();
+        assert_eq!(image.rgba, [128, 128, 128, 255]);
+    }
+}